@@ -0,0 +1,204 @@
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::theme::Theme;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    /// The theme color of a breached threshold, `None` while below it.
+    pub fn color(self, theme: &Theme) -> Option<Color> {
+        match self {
+            Severity::Normal => None,
+            Severity::Warning => Some(theme.warning),
+            Severity::Critical => Some(theme.critical),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Normal => "OK",
+            Severity::Warning => "WARNING",
+            Severity::Critical => "CRITICAL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Threshold {
+    pub warning: f64,
+    pub critical: f64,
+}
+
+impl Threshold {
+    const fn new(warning: f64, critical: f64) -> Self {
+        Self { warning, critical }
+    }
+
+    pub fn check(self, value: f64) -> Severity {
+        if value >= self.critical {
+            Severity::Critical
+        } else if value >= self.warning {
+            Severity::Warning
+        } else {
+            Severity::Normal
+        }
+    }
+}
+
+/// Alert thresholds, the `[alerts]` table of the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Thresholds {
+    pub enabled: bool,
+    /// Used share of all swap, in percent.
+    pub swap_percent: Threshold,
+    /// Used share of a single swap device, in percent.
+    pub device_percent: Threshold,
+    /// Share of `memory.swap.max` a cgroup uses, in percent.
+    pub cgroup_percent: Threshold,
+    /// Swap of a single process, or group when aggregated, in MB.
+    pub process_mb: Threshold,
+    /// Swap-out rate in MB per second.
+    pub swap_out_mb_s: Threshold,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            swap_percent: Threshold::new(75.0, 90.0),
+            device_percent: Threshold::new(75.0, 90.0),
+            cgroup_percent: Threshold::new(75.0, 90.0),
+            process_mb: Threshold::new(1024.0, 4096.0),
+            swap_out_mb_s: Threshold::new(10.0, 50.0),
+        }
+    }
+}
+
+impl Thresholds {
+    /// Name of the first threshold whose warning level lies above its critical level.
+    pub fn invalid(&self) -> Option<&'static str> {
+        [
+            ("swap_percent", self.swap_percent),
+            ("device_percent", self.device_percent),
+            ("cgroup_percent", self.cgroup_percent),
+            ("process_mb", self.process_mb),
+            ("swap_out_mb_s", self.swap_out_mb_s),
+        ]
+        .into_iter()
+        .find(|(_, threshold)| threshold.warning > threshold.critical)
+        .map(|(name, _)| name)
+    }
+
+    fn check(&self, threshold: Threshold, value: f64) -> Severity {
+        if self.enabled {
+            threshold.check(value)
+        } else {
+            Severity::Normal
+        }
+    }
+
+    pub fn swap(&self, percent: f64) -> Severity {
+        self.check(self.swap_percent, percent)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn device(&self, percent: f64) -> Severity {
+        self.check(self.device_percent, percent)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn cgroup(&self, percent: f64) -> Severity {
+        self.check(self.cgroup_percent, percent)
+    }
+
+    pub fn process(&self, kb: f64) -> Severity {
+        self.check(self.process_mb, kb / 1024.0)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn swap_out(&self, kb_per_second: f64) -> Severity {
+        self.check(self.swap_out_mb_s, kb_per_second / 1024.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn threshold_levels_are_inclusive() {
+        let threshold = Threshold::new(75.0, 90.0);
+        assert_eq!(threshold.check(74.9), Severity::Normal);
+        assert_eq!(threshold.check(75.0), Severity::Warning);
+        assert_eq!(threshold.check(89.9), Severity::Warning);
+        assert_eq!(threshold.check(90.0), Severity::Critical);
+    }
+
+    #[test]
+    fn sizes_and_rates_compare_in_mb() {
+        let thresholds = Thresholds::default();
+        assert_eq!(thresholds.process(1023.0 * 1024.0), Severity::Normal);
+        assert_eq!(thresholds.process(1024.0 * 1024.0), Severity::Warning);
+        assert_eq!(thresholds.process(4096.0 * 1024.0), Severity::Critical);
+        #[cfg(target_os = "linux")]
+        {
+            assert_eq!(thresholds.swap_out(9.0 * 1024.0), Severity::Normal);
+            assert_eq!(thresholds.swap_out(50.0 * 1024.0), Severity::Critical);
+        }
+    }
+
+    #[test]
+    fn disabled_alerts_never_fire() {
+        let thresholds = Thresholds {
+            enabled: false,
+            ..Thresholds::default()
+        };
+        assert_eq!(thresholds.swap(100.0), Severity::Normal);
+        assert_eq!(thresholds.process(f64::MAX), Severity::Normal);
+    }
+
+    #[test]
+    fn config_table_overrides_single_thresholds() {
+        let thresholds: Thresholds =
+            toml::from_str("swap_percent = { warning = 50, critical = 60 }\n").unwrap();
+        assert_eq!(thresholds.swap_percent, Threshold::new(50.0, 60.0));
+        assert_eq!(thresholds.process_mb, Thresholds::default().process_mb);
+        assert!(thresholds.enabled);
+
+        assert!(toml::from_str::<Thresholds>("swap = { warning = 1, critical = 2 }\n").is_err());
+        assert!(toml::from_str::<Thresholds>("swap_percent = { warning = 1 }\n").is_err());
+    }
+
+    #[test]
+    fn warning_above_critical_is_invalid() {
+        assert_eq!(Thresholds::default().invalid(), None);
+        let thresholds = Thresholds {
+            process_mb: Threshold::new(100.0, 10.0),
+            swap_out_mb_s: Threshold::new(5.0, 1.0),
+            ..Thresholds::default()
+        };
+        assert_eq!(thresholds.invalid(), Some("process_mb"));
+        let equal = Thresholds {
+            swap_percent: Threshold::new(80.0, 80.0),
+            ..Thresholds::default()
+        };
+        assert_eq!(equal.invalid(), None);
+    }
+
+    #[test]
+    fn severity_colors_follow_the_theme() {
+        let theme = crate::theme::Theme::from(crate::theme::ThemeType::Nord);
+        assert_eq!(Severity::Normal.color(&theme), None);
+        assert_eq!(Severity::Warning.color(&theme), Some(theme.warning));
+        assert_eq!(Severity::Critical.color(&theme), Some(theme.critical));
+        assert!(Severity::Critical > Severity::Warning);
+    }
+}
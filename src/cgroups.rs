@@ -0,0 +1,134 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Counters from `memory.swap.events`.
+#[derive(Debug, Clone, Default)]
+pub struct SwapEvents {
+    pub high: u64,
+    pub max: u64,
+    pub fail: u64,
+}
+
+/// Swap accounting of one cgroup v2 node, sizes in bytes. `memory.swap.current`
+/// already includes every descendant. A limit of `None` means `max`.
+#[derive(Debug, Clone)]
+pub struct CgroupSwap {
+    pub path: String,
+    pub depth: usize,
+    pub current: u64,
+    pub max: Option<u64>,
+    pub high: Option<u64>,
+    pub events: SwapEvents,
+}
+
+impl CgroupSwap {
+    /// Share of `memory.swap.max` in use, when a limit is set.
+    pub fn usage_ratio(&self) -> Option<f64> {
+        self.max.map(|max| {
+            if max == 0 {
+                1.0
+            } else {
+                self.current as f64 / max as f64
+            }
+        })
+    }
+}
+
+/// Finds the cgroup v2 mount with the memory controller enabled, either the
+/// unified hierarchy at `/sys/fs/cgroup` or the hybrid `/sys/fs/cgroup/unified`.
+pub fn cgroup2_root() -> Option<PathBuf> {
+    ["/sys/fs/cgroup", "/sys/fs/cgroup/unified"]
+        .into_iter()
+        .map(PathBuf::from)
+        .find(|root| {
+            fs::read_to_string(root.join("cgroup.controllers"))
+                .is_ok_and(|controllers| controllers.split_whitespace().any(|c| c == "memory"))
+        })
+}
+
+fn read_bytes(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn read_events(path: &Path) -> SwapEvents {
+    let mut events = SwapEvents::default();
+    let Ok(content) = fs::read_to_string(path) else {
+        return events;
+    };
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(name), Some(Ok(value))) = (fields.next(), fields.next().map(str::parse)) else {
+            continue;
+        };
+        match name {
+            "high" => events.high = value,
+            "max" => events.max = value,
+            "fail" => events.fail = value,
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Reads `dir` and its descendants into `out` in tree order, children with the
+/// most swap first. Only branches that use swap or carry a swap limit are kept.
+fn walk(root: &Path, dir: &Path, depth: usize, out: &mut Vec<CgroupSwap>) -> bool {
+    let Some(current) = read_bytes(&dir.join("memory.swap.current")) else {
+        return false;
+    };
+    let node = CgroupSwap {
+        path: format!("/{}", dir.strip_prefix(root).unwrap_or(dir).display()),
+        depth,
+        current,
+        max: read_bytes(&dir.join("memory.swap.max")),
+        high: read_bytes(&dir.join("memory.swap.high")),
+        events: read_events(&dir.join("memory.swap.events")),
+    };
+
+    let mut children: Vec<(u64, PathBuf)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| {
+            let path = entry.path();
+            (
+                read_bytes(&path.join("memory.swap.current")).unwrap_or(0),
+                path,
+            )
+        })
+        .collect();
+    children.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    let interesting = node.current > 0 || node.max.is_some() || node.high.is_some();
+    let index = out.len();
+    out.push(node);
+    let mut keep = interesting;
+    for (_, child) in children {
+        keep |= walk(root, &child, depth + 1, out);
+    }
+    if !keep {
+        out.truncate(index);
+    }
+    keep
+}
+
+/// Returns the swap usage of every cgroup as a depth-first tree, or `None` when
+/// no cgroup v2 hierarchy with the memory controller is mounted.
+pub fn get_cgroup_swap() -> Option<Vec<CgroupSwap>> {
+    let root = cgroup2_root()?;
+    let mut out = Vec::new();
+
+    // the root cgroup has no swap files of its own
+    let mut children: Vec<PathBuf> = fs::read_dir(&root)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
+        .collect();
+    children.sort_by_key(|path| std::cmp::Reverse(read_bytes(&path.join("memory.swap.current"))));
+    for child in children {
+        walk(&root, &child, 0, &mut out);
+    }
+    Some(out)
+}
@@ -0,0 +1,108 @@
+use clap::{ArgGroup, Parser, Subcommand};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use crate::report::OutputFormat;
+use crate::swap_info::{AggregateBy, SizeUnits, SwapMetric};
+use crate::theme::ThemeType;
+
+#[derive(Debug, Parser)]
+#[command(name = "swaptop", version, about)]
+#[command(group(ArgGroup::new("headless").args(["once", "stream"])))]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Unit used to display swap sizes
+    #[arg(short, long, value_enum)]
+    pub unit: Option<SizeUnits>,
+
+    /// Per-process swap figure: VmSwap, or SwapPss which splits shared pages (Linux only)
+    #[arg(short, long, value_enum)]
+    pub metric: Option<SwapMetric>,
+
+    /// Color theme
+    #[arg(short, long, value_enum)]
+    pub theme: Option<ThemeType>,
+
+    /// Refresh interval in milliseconds (1-10000)
+    #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..=10000))]
+    pub interval: Option<u64>,
+
+    /// Start with processes aggregated by the configured group (default: name)
+    #[arg(short, long)]
+    pub aggregate: bool,
+
+    /// Start with processes listed individually, overriding the config
+    #[arg(long, conflicts_with_all = ["aggregate", "group_by"])]
+    pub no_aggregate: bool,
+
+    /// Key processes are aggregated by, implies --aggregate (uid, cgroup, unit and container are Linux only)
+    #[arg(short, long, value_enum)]
+    pub group_by: Option<AggregateBy>,
+
+    /// Start with the swap devices panel visible (Linux only)
+    #[arg(short, long)]
+    pub devices: bool,
+
+    /// Start with the swap devices panel hidden, overriding the config
+    #[arg(long, conflicts_with = "devices")]
+    pub no_devices: bool,
+
+    /// Print a single swap report to stdout instead of starting the TUI
+    #[arg(long, visible_alias = "report")]
+    pub once: bool,
+
+    /// Print one record per refresh interval to stdout until interrupted
+    #[arg(long)]
+    pub stream: bool,
+
+    /// Stop streaming after this many records
+    #[arg(long, requires = "stream")]
+    pub count: Option<u64>,
+
+    /// Maximum number of processes printed in each report
+    #[arg(short = 'n', long, value_name = "ROWS", requires = "headless")]
+    pub limit: Option<usize>,
+
+    /// Output format of the report [default: text, ndjson when streaming]
+    #[arg(short, long, value_enum, requires = "headless")]
+    pub format: Option<OutputFormat>,
+
+    /// Append every refresh to a recording file
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["headless", "replay"])]
+    pub record: Option<PathBuf>,
+
+    /// Replay a recording in the TUI instead of reading live data
+    #[arg(long, value_name = "FILE", conflicts_with = "headless")]
+    pub replay: Option<PathBuf>,
+
+    /// Config file to use instead of $XDG_CONFIG_HOME/swaptop/config.toml
+    #[arg(short, long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Expose swap metrics over HTTP in the Prometheus text format
+    Serve {
+        /// Address to listen on
+        #[arg(short, long, default_value = "127.0.0.1:9798")]
+        listen: SocketAddr,
+
+        /// Maximum number of processes (or groups with --aggregate) exported
+        #[arg(long, default_value_t = 50)]
+        top: usize,
+    },
+
+    /// Fault the swapped pages of a process back into RAM (Linux only)
+    #[cfg(target_os = "linux")]
+    SwapIn {
+        /// Process to swap in
+        pid: u32,
+
+        /// Only print how much would be swapped in and whether it fits in MemAvailable
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::alerts::Thresholds;
+use crate::swap_info::{AggregateBy, SizeUnits, SwapMetric};
+use crate::theme::ThemeType;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub theme: Option<ThemeType>,
+    pub unit: Option<SizeUnits>,
+    pub metric: Option<SwapMetric>,
+    pub interval: Option<u64>,
+    pub aggregate: Option<bool>,
+    pub group_by: Option<AggregateBy>,
+    pub devices: Option<bool>,
+    pub save_on_exit: Option<bool>,
+    pub alerts: Option<Thresholds>,
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("could not determine the configuration directory")]
+    NoConfigDir,
+    #[error("I/O error accessing {0}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("invalid config file {0}: {1}")]
+    Parse(PathBuf, toml::de::Error),
+    #[error("could not serialize config: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("invalid interval {0}ms in config, expected 1-10000")]
+    InvalidInterval(u64),
+    #[error("not overwriting {0}, it failed to load, fix or remove it first")]
+    LoadFailed(PathBuf),
+    #[error("invalid alert threshold {0} in config, warning must not exceed critical")]
+    InvalidThreshold(&'static str),
+}
+
+#[cfg(target_os = "linux")]
+pub fn default_config_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("swaptop").join("config.toml"))
+}
+
+#[cfg(target_os = "windows")]
+pub fn default_config_path() -> Option<PathBuf> {
+    let base = PathBuf::from(std::env::var_os("APPDATA")?);
+    Some(base.join("swaptop").join("config.toml"))
+}
+
+/// Loads the config at `path`. A missing file is not an error and yields `None`.
+pub fn load_config(path: &Path) -> Result<Option<Config>, ConfigError> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(ConfigError::Io(path.to_owned(), e)),
+    };
+
+    let config: Config =
+        toml::from_str(&contents).map_err(|e| ConfigError::Parse(path.to_owned(), e))?;
+
+    if let Some(interval) = config.interval
+        && !(1..=10000).contains(&interval)
+    {
+        return Err(ConfigError::InvalidInterval(interval));
+    }
+
+    if let Some(name) = config.alerts.as_ref().and_then(Thresholds::invalid) {
+        return Err(ConfigError::InvalidThreshold(name));
+    }
+
+    Ok(Some(config))
+}
+
+pub fn save_config(path: &Path, config: &Config) -> Result<(), ConfigError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| ConfigError::Io(parent.to_owned(), e))?;
+    }
+    let contents = toml::to_string_pretty(config)?;
+    fs::write(path, contents).map_err(|e| ConfigError::Io(path.to_owned(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A config path in its own temp directory, removed again when dropped.
+    struct TempConfig(PathBuf);
+
+    impl TempConfig {
+        fn new(test: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("swaptop-{}-{test}", std::process::id()));
+            Self(dir.join("swaptop").join("config.toml"))
+        }
+
+        fn load(&self, contents: &str) -> Result<Option<Config>, ConfigError> {
+            fs::create_dir_all(self.0.parent().unwrap()).unwrap();
+            fs::write(&self.0, contents).unwrap();
+            load_config(&self.0)
+        }
+    }
+
+    impl Drop for TempConfig {
+        fn drop(&mut self) {
+            if let Some(dir) = self.0.parent().and_then(Path::parent) {
+                let _ = fs::remove_dir_all(dir);
+            }
+        }
+    }
+
+    #[test]
+    fn missing_file_is_no_config() {
+        let config = TempConfig::new("config-missing");
+        assert!(matches!(load_config(&config.0), Ok(None)));
+    }
+
+    #[test]
+    fn saved_config_loads_back() {
+        let path = TempConfig::new("config-roundtrip");
+        let config = Config {
+            theme: Some(ThemeType::Nord),
+            unit: Some(SizeUnits::MB),
+            interval: Some(500),
+            group_by: Some(AggregateBy::User),
+            save_on_exit: Some(true),
+            ..Config::default()
+        };
+        // the directory is created on save
+        save_config(&path.0, &config).unwrap();
+
+        let loaded = load_config(&path.0).unwrap().unwrap();
+        assert!(matches!(loaded.theme, Some(ThemeType::Nord)));
+        assert!(matches!(loaded.unit, Some(SizeUnits::MB)));
+        assert_eq!(loaded.interval, Some(500));
+        assert_eq!(loaded.group_by, Some(AggregateBy::User));
+        assert_eq!(loaded.save_on_exit, Some(true));
+        assert_eq!(loaded.aggregate, None);
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_bad_values() {
+        let config = TempConfig::new("config-invalid");
+        assert!(matches!(
+            config.load("colour = \"nord\"\n"),
+            Err(ConfigError::Parse(..))
+        ));
+        assert!(matches!(
+            config.load("unit = \"tb\"\n"),
+            Err(ConfigError::Parse(..))
+        ));
+        assert!(matches!(
+            config.load("interval = \"fast\"\n"),
+            Err(ConfigError::Parse(..))
+        ));
+    }
+
+    #[test]
+    fn interval_must_be_in_range() {
+        let config = TempConfig::new("config-interval");
+        assert!(matches!(
+            config.load("interval = 0\n"),
+            Err(ConfigError::InvalidInterval(0))
+        ));
+        assert!(matches!(
+            config.load("interval = 10001\n"),
+            Err(ConfigError::InvalidInterval(10001))
+        ));
+        assert!(matches!(config.load("interval = 1\n"), Ok(Some(_))));
+        assert!(matches!(config.load("interval = 10000\n"), Ok(Some(_))));
+    }
+
+    #[test]
+    fn alert_thresholds_are_validated() {
+        let config = TempConfig::new("config-alerts");
+        let loaded = config
+            .load("[alerts]\nprocess_mb = { warning = 10, critical = 20 }\n")
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.alerts.unwrap().process_mb.critical, 20.0);
+        assert!(matches!(
+            config.load("[alerts]\ndevice_percent = { warning = 95, critical = 90 }\n"),
+            Err(ConfigError::InvalidThreshold("device_percent"))
+        ));
+    }
+}
@@ -0,0 +1,232 @@
+/// Systemd unit and container a process belongs to, derived from its cgroup paths.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attribution {
+    pub systemd_unit: String,
+    pub container_id: String,
+    pub container_runtime: String,
+}
+
+const SHORT_ID_LEN: usize = 12;
+
+fn is_container_id(id: &str) -> bool {
+    id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Recognizes one path component, e.g. `docker-<id>.scope` under systemd or the
+/// bare `<id>` directory docker and kubelet create with the cgroupfs driver.
+fn container_in(component: &str, parent: Option<&str>, path: &str) -> Option<(String, String)> {
+    let scope = component.strip_suffix(".scope").unwrap_or(component);
+    let prefixed = [
+        ("docker-", "docker"),
+        ("cri-containerd-", "containerd"),
+        ("crio-", "cri-o"),
+        ("libpod-", "podman"),
+    ];
+    for (prefix, runtime) in prefixed {
+        if let Some(id) = scope.strip_prefix(prefix)
+            && is_container_id(id)
+        {
+            return Some((runtime.to_owned(), id[..SHORT_ID_LEN].to_owned()));
+        }
+    }
+
+    if is_container_id(component) {
+        let runtime = match parent {
+            Some("docker") => "docker",
+            _ if path.contains("libpod") => "podman",
+            _ if path.contains("kubepods") => "kubernetes",
+            _ => "containerd",
+        };
+        return Some((runtime.to_owned(), component[..SHORT_ID_LEN].to_owned()));
+    }
+    None
+}
+
+/// The deepest `.service` or `.scope` is the unit that started the process,
+/// a `.slice` is only used when the process sits directly in one.
+fn systemd_unit(path: &str) -> Option<&str> {
+    let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    components
+        .iter()
+        .rev()
+        .find(|c| c.ends_with(".service") || c.ends_with(".scope"))
+        .or_else(|| components.iter().rev().find(|c| c.ends_with(".slice")))
+        .copied()
+}
+
+/// Derives the attribution from every cgroup path of a process, the v1
+/// hierarchies may disagree and the first match wins.
+pub fn attribute<'a>(paths: impl IntoIterator<Item = &'a str>) -> Attribution {
+    let mut attribution = Attribution::default();
+
+    for path in paths {
+        if attribution.systemd_unit.is_empty()
+            && let Some(unit) = systemd_unit(path)
+        {
+            attribution.systemd_unit = unit.to_owned();
+        }
+
+        if attribution.container_id.is_empty() {
+            let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+            let found =
+                components.iter().enumerate().rev().find_map(|(i, c)| {
+                    container_in(c, i.checked_sub(1).map(|p| components[p]), path)
+                });
+            if let Some((runtime, id)) = found {
+                attribution.container_runtime = runtime;
+                attribution.container_id = id;
+            }
+        }
+    }
+
+    attribution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "4f6d1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f";
+
+    /// The paths of a `/proc/<pid>/cgroup` file, `hierarchy:controllers:path` per line.
+    fn paths(cgroup: &str) -> Vec<&str> {
+        cgroup
+            .lines()
+            .filter_map(|line| line.splitn(3, ':').nth(2))
+            .collect()
+    }
+
+    #[test]
+    fn attributes_real_cgroup_files() {
+        let cases = [
+            (
+                format!("0::/system.slice/docker-{ID}.scope"),
+                format!("docker-{ID}.scope"),
+                "docker",
+            ),
+            (
+                format!("12:memory:/docker/{ID}\n11:cpu,cpuacct:/docker/{ID}"),
+                String::new(),
+                "docker",
+            ),
+            (
+                format!(
+                    "0::/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{ID}.scope/container"
+                ),
+                format!("libpod-{ID}.scope"),
+                "podman",
+            ),
+            (
+                format!("0::/machine.slice/libpod-{ID}.scope/container"),
+                format!("libpod-{ID}.scope"),
+                "podman",
+            ),
+            (
+                format!(
+                    "0::/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod0a1b2c3d_4e5f.slice/cri-containerd-{ID}.scope"
+                ),
+                format!("cri-containerd-{ID}.scope"),
+                "containerd",
+            ),
+            (
+                format!(
+                    "0::/kubepods.slice/kubepods-besteffort.slice/kubepods-besteffort-pod0a1b2c3d_4e5f.slice/crio-{ID}.scope"
+                ),
+                format!("crio-{ID}.scope"),
+                "cri-o",
+            ),
+            (
+                format!("0::/kubepods/burstable/pod0a1b2c3d-4e5f/{ID}"),
+                String::new(),
+                "kubernetes",
+            ),
+            (
+                format!("0::/system.slice/containerd.service/k8s.io/{ID}"),
+                "containerd.service".to_owned(),
+                "containerd",
+            ),
+        ];
+
+        for (cgroup, unit, runtime) in cases {
+            let attribution = attribute(paths(&cgroup));
+            assert_eq!(attribution.systemd_unit, unit, "{cgroup}");
+            assert_eq!(attribution.container_runtime, runtime, "{cgroup}");
+            assert_eq!(attribution.container_id, &ID[..SHORT_ID_LEN], "{cgroup}");
+        }
+    }
+
+    #[test]
+    fn attributes_processes_outside_containers() {
+        let cases = [
+            ("0::/system.slice/nginx.service", "nginx.service"),
+            (
+                "0::/user.slice/user-1000.slice/session-2.scope",
+                "session-2.scope",
+            ),
+            (
+                "0::/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox.scope",
+                "app-firefox.scope",
+            ),
+            ("0::/user.slice", "user.slice"),
+            ("0::/init.scope", "init.scope"),
+            ("0::/", ""),
+            // conmon supervises a podman container but is not part of it
+            (
+                "0::/machine.slice/libpod-conmon-4f6d1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f.scope",
+                "libpod-conmon-4f6d1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f.scope",
+            ),
+            (
+                "0::/system.slice/docker-abc123.scope",
+                "docker-abc123.scope",
+            ),
+        ];
+
+        for (cgroup, unit) in cases {
+            let attribution = attribute(paths(cgroup));
+            assert_eq!(attribution.systemd_unit, unit, "{cgroup}");
+            assert_eq!(attribution.container_id, "", "{cgroup}");
+            assert_eq!(attribution.container_runtime, "", "{cgroup}");
+        }
+    }
+
+    #[test]
+    fn first_v1_hierarchy_with_a_match_wins() {
+        let cgroup = format!(
+            "12:pids:/\n11:memory:/system.slice/docker.service\n4:cpu,cpuacct:/docker/{ID}"
+        );
+        let attribution = attribute(paths(&cgroup));
+        assert_eq!(attribution.systemd_unit, "docker.service");
+        assert_eq!(attribution.container_runtime, "docker");
+        assert_eq!(attribution.container_id, &ID[..SHORT_ID_LEN]);
+    }
+
+    #[test]
+    fn container_in_needs_a_full_hex_id() {
+        assert_eq!(container_in(&ID[1..], Some("docker"), ""), None);
+        assert_eq!(
+            container_in(&ID.to_uppercase(), Some("docker"), "").map(|(runtime, _)| runtime),
+            Some("docker".to_owned())
+        );
+        assert_eq!(
+            container_in(&format!("docker-{}.scope", &ID[..63]), None, ""),
+            None
+        );
+    }
+
+    #[test]
+    fn systemd_unit_prefers_the_deepest_service_or_scope() {
+        assert_eq!(
+            systemd_unit("/system.slice/foo.service/sub.slice"),
+            Some("foo.service")
+        );
+        assert_eq!(
+            systemd_unit("/user.slice/user@1000.service/app.slice/a.scope"),
+            Some("a.scope")
+        );
+        assert_eq!(
+            systemd_unit("/user.slice/user-1000.slice"),
+            Some("user-1000.slice")
+        );
+        assert_eq!(systemd_unit("/docker/abc"), None);
+    }
+}
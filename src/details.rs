@@ -0,0 +1,81 @@
+use procfs::WithCurrentSystemInfo;
+use procfs::process::Process;
+
+use crate::swap_info::{SwapDataError, memory_cgroup, read_users};
+
+const HISTORY_LEN: usize = 120;
+
+/// Everything the detail pane shows about a single process, read from procfs.
+#[derive(Debug, Clone)]
+pub struct ProcessDetail {
+    pub pid: u32,
+    pub name: String,
+    pub cmdline: String,
+    pub user: String,
+    pub uid: u32,
+    pub state: String,
+    pub rss_kb: u64,
+    pub swap_kb: u64,
+    pub cgroup: String,
+    pub start_time: String,
+    pub swap_history: Vec<u64>,
+}
+
+impl ProcessDetail {
+    pub fn read(pid: u32) -> Result<Self, SwapDataError> {
+        let mut detail = Self {
+            pid,
+            name: String::new(),
+            cmdline: String::new(),
+            user: String::new(),
+            uid: 0,
+            state: String::new(),
+            rss_kb: 0,
+            swap_kb: 0,
+            cgroup: String::new(),
+            start_time: String::new(),
+            swap_history: Vec::new(),
+        };
+        detail.refresh()?;
+        Ok(detail)
+    }
+
+    /// Rereads the process and appends the current VmSwap to the history.
+    pub fn refresh(&mut self) -> Result<(), SwapDataError> {
+        let process = Process::new(self.pid as i32)?;
+        let status = process.status()?;
+        let stat = process.stat()?;
+
+        self.name = stat.comm.clone();
+        self.cmdline = match process.cmdline() {
+            Ok(args) if !args.is_empty() => args.join(" "),
+            _ => format!("[{}]", stat.comm),
+        };
+        // /etc/passwd is only read again when the process changes its UID
+        if self.user.is_empty() || self.uid != status.ruid {
+            self.uid = status.ruid;
+            self.user = read_users()
+                .remove(&status.ruid)
+                .unwrap_or_else(|| status.ruid.to_string());
+        }
+        self.state = status.state;
+        self.rss_kb = status.vmrss.unwrap_or_default();
+        self.swap_kb = status.vmswap.unwrap_or_default();
+        self.cgroup = process
+            .cgroups()
+            .ok()
+            .and_then(|cgroups| memory_cgroup(&cgroups.0))
+            .unwrap_or_else(|| "unknown".into());
+        self.start_time = stat
+            .starttime()
+            .get()
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|_| "unknown".into());
+
+        self.swap_history.push(self.swap_kb);
+        if self.swap_history.len() > HISTORY_LEN {
+            self.swap_history.drain(0..1);
+        }
+        Ok(())
+    }
+}
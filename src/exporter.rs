@@ -0,0 +1,370 @@
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+
+use crate::report::Snapshot;
+use crate::swap_info::{AggregateBy, SizeUnits, SwapMetric};
+
+/// Read and write timeout of a connection, a stalled client is dropped after it.
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Serves `/metrics` in the Prometheus text format. Connections are handled one
+/// at a time, the timeout bounds how long a stalled client holds up the next.
+pub fn serve(
+    listen: SocketAddr,
+    metric: SwapMetric,
+    group_by: Option<AggregateBy>,
+    top: usize,
+) -> color_eyre::Result<()> {
+    let listener = TcpListener::bind(listen)?;
+    eprintln!(
+        "swaptop: serving metrics on http://{}/metrics",
+        listener.local_addr()?
+    );
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("swaptop: failed to accept connection: {e}");
+                continue;
+            }
+        };
+        if let Err(e) = handle_connection(stream, metric, group_by, top) {
+            eprintln!("swaptop: failed to handle request: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    metric: SwapMetric,
+    group_by: Option<AggregateBy>,
+    top: usize,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // drain the headers, the body of a GET is ignored
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => {
+            match Snapshot::collect(SizeUnits::KB, metric, group_by, Some(top)) {
+                Ok(snapshot) => (
+                    "200 OK",
+                    "text/plain; version=0.0.4; charset=utf-8",
+                    render_metrics(&snapshot),
+                ),
+                Err(e) => (
+                    "500 Internal Server Error",
+                    "text/plain; charset=utf-8",
+                    format!("failed to read swap information: {e}\n"),
+                ),
+            }
+        }
+        ("GET", "/") => (
+            "200 OK",
+            "text/html; charset=utf-8",
+            "<html><body><h1>swaptop</h1><a href=\"/metrics\">metrics</a></body></html>\n"
+                .to_owned(),
+        ),
+        ("GET", _) => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "not found\n".to_owned(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            "method not allowed\n".to_owned(),
+        ),
+    };
+
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn write_header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+}
+
+/// Renders a snapshot collected in KB. All sizes are exported in bytes.
+pub fn render_metrics(snapshot: &Snapshot) -> String {
+    let mut out = String::new();
+
+    write_header(&mut out, "swaptop_swap_total_bytes", "Total swap space.");
+    let _ = writeln!(
+        out,
+        "swaptop_swap_total_bytes {}",
+        snapshot.swap.total_swap * 1024
+    );
+    write_header(&mut out, "swaptop_swap_used_bytes", "Used swap space.");
+    let _ = writeln!(
+        out,
+        "swaptop_swap_used_bytes {}",
+        snapshot.swap.used_swap * 1024
+    );
+
+    #[cfg(target_os = "linux")]
+    {
+        let devices = &snapshot.swap.swap_devices;
+        write_header(
+            &mut out,
+            "swaptop_swap_device_size_bytes",
+            "Size of a swap device.",
+        );
+        for d in devices {
+            let _ = writeln!(
+                out,
+                "swaptop_swap_device_size_bytes{{device=\"{}\",type=\"{}\"}} {}",
+                escape_label(&d.name),
+                escape_label(&d.kind),
+                d.size_kb as u64 * 1024
+            );
+        }
+        write_header(
+            &mut out,
+            "swaptop_swap_device_used_bytes",
+            "Used space of a swap device.",
+        );
+        for d in devices {
+            let _ = writeln!(
+                out,
+                "swaptop_swap_device_used_bytes{{device=\"{}\",type=\"{}\"}} {}",
+                escape_label(&d.name),
+                escape_label(&d.kind),
+                d.used_kb as u64 * 1024
+            );
+        }
+        write_header(
+            &mut out,
+            "swaptop_swap_device_priority",
+            "Priority of a swap device.",
+        );
+        for d in devices {
+            let _ = writeln!(
+                out,
+                "swaptop_swap_device_priority{{device=\"{}\",type=\"{}\"}} {}",
+                escape_label(&d.name),
+                escape_label(&d.kind),
+                d.priority
+            );
+        }
+    }
+
+    if snapshot.aggregated {
+        let key = snapshot.group_by.unwrap_or_default().label();
+        write_header(
+            &mut out,
+            "swaptop_process_group_swap_bytes",
+            &format!(
+                "Swap used by all processes sharing a {key} ({}).",
+                snapshot.metric.label()
+            ),
+        );
+        for p in &snapshot.processes {
+            let _ = writeln!(
+                out,
+                "swaptop_process_group_swap_bytes{{{key}=\"{}\"}} {}",
+                escape_label(&p.name),
+                p.swap_size as u64 * 1024
+            );
+        }
+        write_header(
+            &mut out,
+            "swaptop_process_group_count",
+            &format!("Number of swapping processes sharing a {key}."),
+        );
+        for p in &snapshot.processes {
+            let _ = writeln!(
+                out,
+                "swaptop_process_group_count{{{key}=\"{}\"}} {}",
+                escape_label(&p.name),
+                p.pid
+            );
+        }
+    } else {
+        write_header(
+            &mut out,
+            "swaptop_process_swap_bytes",
+            &format!("Swap used by a process ({}).", snapshot.metric.label()),
+        );
+        for p in &snapshot.processes {
+            let _ = writeln!(
+                out,
+                "swaptop_process_swap_bytes{{pid=\"{}\",name=\"{}\"}} {}",
+                p.pid,
+                escape_label(&p.name),
+                p.swap_size as u64 * 1024
+            );
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swap_info::{ProcessSwapInfo, SwapUpdate};
+    use std::io::Read;
+
+    fn process(pid: u32, name: &str, swap_size: f64) -> ProcessSwapInfo {
+        ProcessSwapInfo {
+            pid,
+            name: name.to_owned(),
+            swap_size,
+            user: String::new(),
+            uid: None,
+            ppid: 0,
+            parent: String::new(),
+            cgroup: String::new(),
+            systemd_unit: String::new(),
+            container_id: String::new(),
+            container_runtime: String::new(),
+        }
+    }
+
+    // the update is needless on Windows, where SwapUpdate only has the totals
+    #[cfg_attr(target_os = "windows", allow(clippy::needless_update))]
+    fn snapshot(group_by: Option<AggregateBy>, processes: Vec<ProcessSwapInfo>) -> Snapshot {
+        Snapshot {
+            timestamp: 0,
+            hostname: "test".to_owned(),
+            unit: SizeUnits::KB,
+            metric: SwapMetric::VmSwap,
+            aggregated: group_by.is_some(),
+            group_by,
+            swap: SwapUpdate {
+                total_swap: 2048,
+                used_swap: 512,
+                ..SwapUpdate::default()
+            },
+            processes,
+        }
+    }
+
+    #[test]
+    fn escape_label_escapes_backslash_quote_and_newline() {
+        assert_eq!(escape_label("plain"), "plain");
+        assert_eq!(escape_label(r"C:\tmp"), r"C:\\tmp");
+        assert_eq!(escape_label(r#"say "hi""#), r#"say \"hi\""#);
+        assert_eq!(escape_label("two\nlines"), r"two\nlines");
+    }
+
+    #[test]
+    fn render_metrics_exports_totals_in_bytes() {
+        let text = render_metrics(&snapshot(None, Vec::new()));
+        assert!(text.contains("# HELP swaptop_swap_total_bytes Total swap space.\n"));
+        assert!(text.contains("# TYPE swaptop_swap_total_bytes gauge\n"));
+        assert!(text.contains("\nswaptop_swap_total_bytes 2097152\n"));
+        assert!(text.contains("\nswaptop_swap_used_bytes 524288\n"));
+    }
+
+    #[test]
+    fn render_metrics_labels_processes_by_pid_and_escaped_name() {
+        let text = render_metrics(&snapshot(None, vec![process(42, "a\"b", 10.0)]));
+        assert!(
+            text.contains("# HELP swaptop_process_swap_bytes Swap used by a process (VmSwap).\n")
+        );
+        assert!(text.contains("swaptop_process_swap_bytes{pid=\"42\",name=\"a\\\"b\"} 10240\n"));
+        assert!(!text.contains("swaptop_process_group_swap_bytes"));
+    }
+
+    #[test]
+    fn render_metrics_labels_groups_by_key() {
+        let text = render_metrics(&snapshot(
+            Some(AggregateBy::User),
+            vec![process(3, "root", 4.0)],
+        ));
+        assert!(text.contains("swaptop_process_group_swap_bytes{user=\"root\"} 4096\n"));
+        assert!(text.contains("swaptop_process_group_count{user=\"root\"} 3\n"));
+        assert!(!text.contains("swaptop_process_swap_bytes"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn render_metrics_exports_swap_devices() {
+        let mut snapshot = snapshot(None, Vec::new());
+        snapshot.swap.swap_devices = vec![crate::swap_info::InfoSwap {
+            name: "/swapfile".to_owned(),
+            kind: "file".to_owned(),
+            size_kb: 1024.0,
+            used_kb: 256.0,
+            priority: -2,
+        }];
+        let text = render_metrics(&snapshot);
+        assert!(text.contains(
+            "swaptop_swap_device_size_bytes{device=\"/swapfile\",type=\"file\"} 1048576\n"
+        ));
+        assert!(text.contains(
+            "swaptop_swap_device_used_bytes{device=\"/swapfile\",type=\"file\"} 262144\n"
+        ));
+        assert!(
+            text.contains("swaptop_swap_device_priority{device=\"/swapfile\",type=\"file\"} -2\n")
+        );
+    }
+
+    /// Sends `request` to `handle_connection` over a loopback socket and returns the response.
+    fn request(request: &str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+        client.write_all(request.as_bytes()).unwrap();
+        handle_connection(server, SwapMetric::VmSwap, None, 10).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_index_and_rejects_other_requests() {
+        let index = request("GET / HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        assert!(index.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(index.contains("<a href=\"/metrics\">"));
+
+        let missing = request("GET /nope HTTP/1.1\r\n\r\n");
+        assert!(missing.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(missing.ends_with("\r\n\r\nnot found\n"));
+
+        let post = request("POST /metrics HTTP/1.1\r\nContent-Length: 0\r\n\r\n");
+        assert!(post.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+    }
+
+    #[test]
+    fn serves_metrics() {
+        let response = request("GET /metrics HTTP/1.1\r\n\r\n");
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(head.contains("Content-Type: text/plain; version=0.0.4; charset=utf-8"));
+        assert!(head.contains(&format!("Content-Length: {}", body.len())));
+        assert!(body.contains("# TYPE swaptop_swap_total_bytes gauge\n"));
+    }
+}
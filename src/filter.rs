@@ -0,0 +1,94 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::swap_info::ProcessSwapInfo;
+
+/// Case-insensitive process filter. The pattern is used as a regex when it
+/// compiles and as a plain substring otherwise, so typing `[` never errors.
+#[derive(Debug, Clone)]
+pub enum ProcessFilter {
+    Regex(Regex),
+    Substring(String),
+}
+
+impl ProcessFilter {
+    pub fn new(pattern: &str) -> Option<Self> {
+        if pattern.is_empty() {
+            return None;
+        }
+
+        Some(
+            match RegexBuilder::new(pattern).case_insensitive(true).build() {
+                Ok(regex) => ProcessFilter::Regex(regex),
+                Err(_) => ProcessFilter::Substring(pattern.to_lowercase()),
+            },
+        )
+    }
+
+    fn matches_str(&self, value: &str) -> bool {
+        match self {
+            ProcessFilter::Regex(regex) => regex.is_match(value),
+            ProcessFilter::Substring(pattern) => value.to_lowercase().contains(pattern),
+        }
+    }
+
+    /// Matches against the process name, PID and owning user.
+    pub fn matches(&self, process: &ProcessSwapInfo) -> bool {
+        self.matches_str(&process.name)
+            || self.matches_str(&process.pid.to_string())
+            || self.matches_str(&process.user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, user: &str) -> ProcessSwapInfo {
+        ProcessSwapInfo {
+            pid,
+            name: name.to_owned(),
+            swap_size: 0.0,
+            user: user.to_owned(),
+            uid: None,
+            ppid: 0,
+            parent: String::new(),
+            cgroup: String::new(),
+            systemd_unit: String::new(),
+            container_id: String::new(),
+            container_runtime: String::new(),
+        }
+    }
+
+    #[test]
+    fn empty_pattern_is_no_filter() {
+        assert!(ProcessFilter::new("").is_none());
+    }
+
+    #[test]
+    fn valid_patterns_are_regexes() {
+        let filter = ProcessFilter::new("^fire.*x$").unwrap();
+        assert!(matches!(filter, ProcessFilter::Regex(_)));
+        assert!(filter.matches(&process(1, "Firefox", "alice")));
+        assert!(!filter.matches(&process(2, "firefox-bin", "alice")));
+    }
+
+    #[test]
+    fn invalid_regexes_fall_back_to_substrings() {
+        let filter = ProcessFilter::new("[Web").unwrap();
+        assert!(matches!(&filter, ProcessFilter::Substring(pattern) if pattern == "[web"));
+        assert!(filter.matches(&process(1, "worker [web]", "")));
+        assert!(!filter.matches(&process(2, "web", "")));
+    }
+
+    #[test]
+    fn matches_name_pid_and_user_ignoring_case() {
+        let filter = ProcessFilter::new("ROOT").unwrap();
+        assert!(filter.matches(&process(1, "bash", "root")));
+        assert!(filter.matches(&process(2, "chroot", "alice")));
+        assert!(!filter.matches(&process(3, "bash", "alice")));
+
+        let filter = ProcessFilter::new("^42$").unwrap();
+        assert!(filter.matches(&process(42, "bash", "alice")));
+        assert!(!filter.matches(&process(420, "bash", "alice")));
+    }
+}
@@ -1,13 +1,70 @@
+mod alerts;
+#[cfg(target_os = "linux")]
+mod cgroups;
+mod cli;
+mod config;
+#[cfg(target_os = "linux")]
+mod containers;
+#[cfg(target_os = "linux")]
+mod details;
+mod exporter;
+mod filter;
+#[cfg(target_os = "linux")]
+mod mappings;
+#[cfg(target_os = "linux")]
+mod pressure;
+mod recording;
+mod report;
+#[cfg(target_os = "linux")]
+mod signals;
 mod swap_info;
+#[cfg(target_os = "linux")]
+mod swapctl;
+#[cfg(target_os = "linux")]
+mod swapin;
 mod theme;
+#[cfg(target_os = "linux")]
+mod tree;
+#[cfg(target_os = "linux")]
+mod zram;
+#[cfg(target_os = "linux")]
+mod zswap;
 
+use crate::swap_info::{
+    AggregateBy, ProcessSwapInfo, SortColumn, SwapMetric, SwapUpdate, aggregate_processes,
+    convert_swap, sort_processes,
+};
 #[cfg(target_os = "linux")]
-use crate::swap_info::find_mount_device;
-use crate::swap_info::{SwapUpdate, aggregate_processes, convert_swap};
+use crate::swap_info::{InfoSwap, find_mount_device};
 
+use crate::alerts::{Severity, Thresholds};
+#[cfg(target_os = "linux")]
+use crate::cgroups::{CgroupSwap, get_cgroup_swap};
+use crate::cli::{Cli, Command};
+use crate::config::{Config, ConfigError, default_config_path, load_config, save_config};
+#[cfg(target_os = "linux")]
+use crate::details::ProcessDetail;
+use crate::filter::ProcessFilter;
+#[cfg(target_os = "linux")]
+use crate::mappings::SwapBreakdown;
+#[cfg(target_os = "linux")]
+use crate::pressure::{MemoryPressure, stall_rate};
+use crate::recording::{Recorder, Replay};
+use crate::report::OutputFormat;
+#[cfg(target_os = "linux")]
+use crate::signals::{Signal, SignalDialog};
+#[cfg(target_os = "linux")]
+use crate::swapctl::{DeviceAction, DeviceManager, MAX_PRIORITY, NewSwapFile};
+#[cfg(target_os = "linux")]
+use crate::swapin::SwapIn;
 use crate::theme::{Theme, ThemeType};
+#[cfg(target_os = "linux")]
+use crate::tree::{ProcessTree, TreeRow};
+use clap::Parser;
 use color_eyre::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+#[cfg(target_os = "linux")]
+use ratatui::widgets::{Borders, Clear, Gauge, LineGauge, Sparkline, Wrap};
 use ratatui::{
     DefaultTerminal, Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -19,35 +76,218 @@ use ratatui::{
         ScrollbarOrientation, ScrollbarState,
     },
 };
+#[cfg(target_os = "linux")]
+use std::collections::HashSet;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use swap_info::{SizeUnits, get_chart_info, get_processes_using_swap};
 
 const LINUX: bool = cfg!(target_os = "linux");
+const STATUS_DURATION: Duration = Duration::from_secs(5);
+/// On and off time of the alert banner.
+const ALERT_FLASH: Duration = Duration::from_millis(500);
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
+    let cli = Cli::parse();
+    let mut app = App::new();
+
+    // without the TUI there is no status line to show config errors in
+    let headless = cli.once || cli.stream || cli.command.is_some();
+    app.config_path = cli.config.clone().or_else(default_config_path);
+    if let Some(path) = &app.config_path {
+        match load_config(path) {
+            Ok(Some(config)) => app.apply_config(&config),
+            Ok(None) => {}
+            Err(e) => {
+                app.config_load_failed = true;
+                if headless {
+                    eprintln!("swaptop: {e}");
+                } else {
+                    app.set_status(e.to_string());
+                }
+            }
+        }
+    }
+    app.apply_cli(&cli);
+
+    if let Some(Command::Serve { listen, top }) = cli.command {
+        return exporter::serve(listen, app.swap_metric, app.group_by(), top);
+    }
+
+    #[cfg(target_os = "linux")]
+    if let Some(Command::SwapIn { pid, dry_run }) = cli.command {
+        return swapin::run(pid, dry_run);
+    }
+
+    if cli.once {
+        return report::print_report(
+            app.swap_size_unit.clone(),
+            app.swap_metric,
+            app.group_by(),
+            cli.limit,
+            cli.format.unwrap_or(OutputFormat::Text),
+        );
+    }
+
+    if cli.stream {
+        return report::stream_report(
+            app.swap_size_unit.clone(),
+            app.swap_metric,
+            app.group_by(),
+            cli.limit,
+            cli.format.unwrap_or(OutputFormat::Ndjson),
+            Duration::from_millis(app.timeout),
+            cli.count,
+        );
+    }
+
+    if let Some(path) = &cli.replay {
+        app.replay = Some(Replay::load(path)?);
+    }
+    if let Some(path) = &cli.record {
+        app.recorder = Some(Recorder::create(path)?);
+    }
+
     let terminal = ratatui::init();
-    let result = App::new().run(terminal);
+    let result = app.run(terminal);
     ratatui::restore();
     result
 }
 
-#[derive(Debug, Default)]
+/// What the chart plots, cycled with `c`.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum ChartMode {
+    #[default]
+    Usage,
+    SwapIo,
+    Pressure,
+}
+
+#[cfg(target_os = "linux")]
+impl ChartMode {
+    fn next(self) -> Self {
+        match self {
+            ChartMode::Usage => ChartMode::SwapIo,
+            ChartMode::SwapIo => ChartMode::Pressure,
+            ChartMode::Pressure => ChartMode::Usage,
+        }
+    }
+}
+
+/// KB per second between two readings of a cumulative counter.
+#[cfg(target_os = "linux")]
+fn swap_rate(previous_kb: u64, current_kb: u64, seconds: f64) -> f64 {
+    if seconds <= 0.0 {
+        return 0.0;
+    }
+    current_kb.saturating_sub(previous_kb) as f64 / seconds
+}
+
+/// Used share of a swap device in percent.
+#[cfg(target_os = "linux")]
+fn device_percent(device: &InfoSwap) -> f64 {
+    if device.size_kb <= 0.0 {
+        return 0.0;
+    }
+    device.used_kb / device.size_kb * 100.0
+}
+
+/// Cuts `text` to `width` characters, marking the cut with an ellipsis.
+fn fit_column(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_owned();
+    }
+    let mut fitted: String = text.chars().take(width - 1).collect();
+    fitted.push('…');
+    fitted
+}
+
+#[derive(Debug)]
 pub struct App {
     running: bool,
     display_devices: bool,
     pub vertical_scroll_state: ScrollbarState,
     pub vertical_scroll: usize,
     pub swap_size_unit: crate::SizeUnits,
+    pub swap_metric: SwapMetric,
     pub swap_processes_lines: Vec<Line<'static>>,
     pub last_update: Option<Instant>,
     pub chart_info: SwapUpdate,
     pub aggregated: bool,
+    aggregate_by: AggregateBy,
     current_theme: ThemeType,
     time_window: [f64; 2],
     chart_data: Vec<(f64, f64)>,
     timeout: u64,
     visible_height: usize,
+    config_path: Option<PathBuf>,
+    config_load_failed: bool,
+    save_on_exit: bool,
+    status_message: Option<(String, Instant)>,
+    processes: Vec<ProcessSwapInfo>,
+    recorder: Option<Recorder>,
+    replay: Option<Replay>,
+    sort_column: SortColumn,
+    sort_descending: bool,
+    filter_input: String,
+    filter: Option<ProcessFilter>,
+    filter_editing: bool,
+    process_rows: Vec<ProcessSwapInfo>,
+    selection: usize,
+    selected_key: Option<String>,
+    alerts: Thresholds,
+    /// Start of the banner flash cycle, monotonic unlike the wall clock.
+    alert_epoch: Instant,
+    #[cfg(target_os = "linux")]
+    detail: Option<ProcessDetail>,
+    #[cfg(target_os = "linux")]
+    breakdown: Option<SwapBreakdown>,
+    #[cfg(target_os = "linux")]
+    breakdown_scroll: usize,
+    #[cfg(target_os = "linux")]
+    chart_mode: ChartMode,
+    #[cfg(target_os = "linux")]
+    swap_in_data: Vec<(f64, f64)>,
+    #[cfg(target_os = "linux")]
+    swap_out_data: Vec<(f64, f64)>,
+    #[cfg(target_os = "linux")]
+    last_swap_io: Option<(u64, u64)>,
+    #[cfg(target_os = "linux")]
+    pressure_some_data: Vec<(f64, f64)>,
+    #[cfg(target_os = "linux")]
+    pressure_full_data: Vec<(f64, f64)>,
+    #[cfg(target_os = "linux")]
+    last_stall_us: Option<u64>,
+    #[cfg(target_os = "linux")]
+    stall_rate: Option<f64>,
+    #[cfg(target_os = "linux")]
+    cgroup_tree: Option<Vec<CgroupSwap>>,
+    #[cfg(target_os = "linux")]
+    cgroup_scroll: usize,
+    #[cfg(target_os = "linux")]
+    show_attribution: bool,
+    #[cfg(target_os = "linux")]
+    tree_view: bool,
+    #[cfg(target_os = "linux")]
+    tree_rows: Vec<TreeRow>,
+    #[cfg(target_os = "linux")]
+    collapsed: HashSet<u32>,
+    #[cfg(target_os = "linux")]
+    signal_dialog: Option<SignalDialog>,
+    #[cfg(target_os = "linux")]
+    swap_in: Option<SwapIn>,
+    #[cfg(target_os = "linux")]
+    device_manager: Option<DeviceManager>,
+    #[cfg(target_os = "linux")]
+    quit_pending: bool,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl App {
@@ -58,69 +298,529 @@ impl App {
             vertical_scroll_state: ScrollbarState::default(),
             vertical_scroll: 0,
             swap_size_unit: SizeUnits::KB,
+            swap_metric: SwapMetric::VmSwap,
             swap_processes_lines: Vec::new(),
             last_update: None,
             chart_info: SwapUpdate::default(),
             aggregated: false,
+            aggregate_by: AggregateBy::Name,
             current_theme: ThemeType::Dracula,
             time_window: [0.0, 60.0],
             chart_data: Vec::new(),
             timeout: 1000,
             visible_height: 0,
+            config_path: None,
+            config_load_failed: false,
+            save_on_exit: false,
+            status_message: None,
+            processes: Vec::new(),
+            recorder: None,
+            replay: None,
+            sort_column: SortColumn::Swap,
+            sort_descending: true,
+            filter_input: String::new(),
+            filter: None,
+            filter_editing: false,
+            process_rows: Vec::new(),
+            selection: 0,
+            selected_key: None,
+            alerts: Thresholds::default(),
+            alert_epoch: Instant::now(),
+            #[cfg(target_os = "linux")]
+            detail: None,
+            #[cfg(target_os = "linux")]
+            breakdown: None,
+            #[cfg(target_os = "linux")]
+            breakdown_scroll: 0,
+            #[cfg(target_os = "linux")]
+            chart_mode: ChartMode::Usage,
+            #[cfg(target_os = "linux")]
+            swap_in_data: Vec::new(),
+            #[cfg(target_os = "linux")]
+            swap_out_data: Vec::new(),
+            #[cfg(target_os = "linux")]
+            last_swap_io: None,
+            #[cfg(target_os = "linux")]
+            pressure_some_data: Vec::new(),
+            #[cfg(target_os = "linux")]
+            pressure_full_data: Vec::new(),
+            #[cfg(target_os = "linux")]
+            last_stall_us: None,
+            #[cfg(target_os = "linux")]
+            stall_rate: None,
+            #[cfg(target_os = "linux")]
+            cgroup_tree: None,
+            #[cfg(target_os = "linux")]
+            cgroup_scroll: 0,
+            #[cfg(target_os = "linux")]
+            show_attribution: false,
+            #[cfg(target_os = "linux")]
+            tree_view: false,
+            #[cfg(target_os = "linux")]
+            tree_rows: Vec::new(),
+            #[cfg(target_os = "linux")]
+            collapsed: HashSet::new(),
+            #[cfg(target_os = "linux")]
+            signal_dialog: None,
+            #[cfg(target_os = "linux")]
+            swap_in: None,
+            #[cfg(target_os = "linux")]
+            device_manager: None,
+            #[cfg(target_os = "linux")]
+            quit_pending: false,
+        }
+    }
+
+    fn apply_config(&mut self, config: &Config) {
+        if let Some(unit) = &config.unit {
+            self.swap_size_unit = unit.clone();
+        }
+        if let Some(metric) = config.metric {
+            self.swap_metric = metric;
+        }
+        if let Some(theme) = config.theme {
+            self.current_theme = theme;
+        }
+        if let Some(interval) = config.interval {
+            self.timeout = interval;
+        }
+        if let Some(aggregate) = config.aggregate {
+            self.aggregated = aggregate;
+        }
+        if let Some(group_by) = config.group_by {
+            self.aggregate_by = group_by;
+        }
+        if let Some(devices) = config.devices {
+            self.display_devices = LINUX && devices;
+        }
+        if let Some(save_on_exit) = config.save_on_exit {
+            self.save_on_exit = save_on_exit;
+        }
+        if let Some(alerts) = config.alerts {
+            self.alerts = alerts;
+        }
+    }
+
+    fn to_config(&self) -> Config {
+        Config {
+            theme: Some(self.current_theme),
+            unit: Some(self.swap_size_unit.clone()),
+            metric: Some(self.swap_metric),
+            interval: Some(self.timeout),
+            aggregate: Some(self.aggregated),
+            group_by: Some(self.aggregate_by),
+            devices: Some(self.display_devices),
+            save_on_exit: Some(self.save_on_exit),
+            alerts: Some(self.alerts),
+        }
+    }
+
+    fn apply_cli(&mut self, cli: &Cli) {
+        if let Some(unit) = &cli.unit {
+            self.swap_size_unit = unit.clone();
+        }
+        if let Some(metric) = cli.metric {
+            self.swap_metric = metric;
+        }
+        if let Some(theme) = cli.theme {
+            self.current_theme = theme;
+        }
+        if let Some(interval) = cli.interval {
+            self.timeout = interval;
+        }
+        if cli.aggregate {
+            self.aggregated = true;
+        }
+        if cli.no_aggregate {
+            self.aggregated = false;
+        }
+        if let Some(group_by) = cli.group_by {
+            self.aggregate_by = group_by;
+            self.aggregated = true;
+        }
+        if cli.devices {
+            self.display_devices = LINUX;
+        }
+        if cli.no_devices {
+            self.display_devices = false;
+        }
+    }
+
+    fn group_by(&self) -> Option<AggregateBy> {
+        self.aggregated.then_some(self.aggregate_by)
+    }
+
+    fn write_config(&self) -> Result<PathBuf, ConfigError> {
+        let path = self.config_path.clone().ok_or(ConfigError::NoConfigDir)?;
+        // the file may only have a typo, keep it instead of replacing it with defaults
+        if self.config_load_failed {
+            return Err(ConfigError::LoadFailed(path));
+        }
+        save_config(&path, &self.to_config())?;
+        Ok(path)
+    }
+
+    fn save_settings(&mut self) {
+        match self.write_config() {
+            Ok(path) => self.set_status(format!("settings saved to {}", path.display())),
+            Err(e) => self.set_status(e.to_string()),
+        }
+    }
+
+    fn set_status(&mut self, message: impl Into<String>) {
+        self.status_message = Some((message.into(), Instant::now()));
+    }
+
+    fn status_line(&self, theme: &Theme) -> Line<'static> {
+        match &self.status_message {
+            Some((message, since)) if since.elapsed() < STATUS_DURATION => {
+                Line::from(format!(" {} ", message))
+                    .fg(theme.secondary)
+                    .bold()
+                    .centered()
+            }
+            _ => Line::from(""),
         }
     }
 
     #[cfg(target_os = "linux")]
     pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
         self.running = true;
-        self.swap_processes_lines = self.create_process_lines(self.aggregated);
-        self.chart_info = get_chart_info(self.swap_size_unit.to_owned())?;
+        self.refresh_data()?;
         self.last_update = Some(Instant::now());
+        if self.replay.is_some() {
+            self.rebuild_replay_chart();
+        } else {
+            self.record_update();
+        }
 
         while self.running {
-            if event::poll(Duration::from_millis(100))? {
+            // keep the swap-in going between key presses
+            let poll = if self.swap_in.as_ref().is_some_and(|s| s.started) {
+                Duration::ZERO
+            } else {
+                Duration::from_millis(100)
+            };
+            if event::poll(poll)? {
                 self.handle_crossterm_events()?;
             }
+            self.step_swap_in();
+            self.poll_device_action();
 
-            if let Some(last_update) = self.last_update
+            if let Some(replay) = &mut self.replay {
+                if replay.tick() {
+                    self.on_replay_step();
+                }
+            } else if let Some(last_update) = self.last_update
                 && last_update.elapsed() >= Duration::from_millis(self.timeout)
             {
-                self.chart_info = get_chart_info(self.swap_size_unit.to_owned())?;
+                self.refresh_data()?;
                 self.update_chart_data();
                 self.last_update = Some(Instant::now());
-                self.swap_processes_lines = self.create_process_lines(self.aggregated);
+                self.record_update();
             }
 
             terminal.draw(|frame| self.render(frame))?;
         }
+
+        if self.save_on_exit {
+            self.write_config()?;
+        }
         Ok(())
     }
 
     #[cfg(target_os = "windows")]
     pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
         self.running = true;
-        self.swap_processes_lines = self.create_process_lines(self.aggregated);
-        self.chart_info = get_chart_info()?;
+        self.refresh_data()?;
         self.last_update = Some(Instant::now());
+        if self.replay.is_some() {
+            self.rebuild_replay_chart();
+        } else {
+            self.record_update();
+        }
 
         while self.running {
             if event::poll(Duration::from_millis(100))? {
                 self.handle_crossterm_events()?;
             }
 
-            if let Some(last_update) = self.last_update
+            if let Some(replay) = &mut self.replay {
+                if replay.tick() {
+                    self.on_replay_step();
+                }
+            } else if let Some(last_update) = self.last_update
                 && last_update.elapsed() >= Duration::from_millis(self.timeout)
             {
-                self.chart_info = get_chart_info()?;
+                self.refresh_data()?;
                 self.update_chart_data();
                 self.last_update = Some(Instant::now());
-                self.swap_processes_lines = self.create_process_lines(self.aggregated);
+                self.record_update();
             }
 
             terminal.draw(|frame| self.render(frame))?;
         }
+
+        if self.save_on_exit {
+            self.write_config()?;
+        }
+        Ok(())
+    }
+    /// Reloads the swap totals and the process list, either from procfs or,
+    /// when replaying, from the current recorded update.
+    #[cfg(target_os = "linux")]
+    fn refresh_data(&mut self) -> Result<()> {
+        if let Some(replay) = &self.replay {
+            let update = replay.current();
+            self.swap_metric = update.metric;
+            self.chart_info = update.swap_in(&self.swap_size_unit);
+            self.processes = update.processes_in(&self.swap_size_unit);
+        } else {
+            self.chart_info = get_chart_info(self.swap_size_unit.to_owned())?;
+            self.processes =
+                get_processes_using_swap(self.swap_size_unit.clone(), self.swap_metric)
+                    .unwrap_or_default();
+            self.refresh_detail();
+            self.refresh_breakdown();
+            if self.cgroup_tree.is_some() {
+                self.cgroup_tree = get_cgroup_swap();
+            }
+        }
+        self.rebuild_process_lines();
         Ok(())
     }
+
+    #[cfg(target_os = "windows")]
+    fn refresh_data(&mut self) -> Result<()> {
+        if let Some(replay) = &self.replay {
+            let update = replay.current();
+            self.swap_metric = update.metric;
+            self.chart_info = update.swap_in(&self.swap_size_unit);
+            self.processes = update.processes_in(&self.swap_size_unit);
+        } else {
+            self.chart_info = get_chart_info()?;
+            self.processes =
+                get_processes_using_swap(self.swap_size_unit.clone(), self.swap_metric)
+                    .unwrap_or_default();
+        }
+        self.rebuild_process_lines();
+        Ok(())
+    }
+
+    fn record_update(&mut self) {
+        let Some(recorder) = &mut self.recorder else {
+            return;
+        };
+        if let Err(e) = recorder.record(
+            &self.swap_size_unit,
+            self.swap_metric,
+            &self.chart_info,
+            &self.processes,
+        ) {
+            self.recorder = None;
+            self.set_status(format!("recording stopped: {e}"));
+        }
+    }
+
+    fn on_replay_step(&mut self) {
+        let _ = self.refresh_data();
+        self.rebuild_replay_chart();
+    }
+
+    /// Rebuilds the chart from the recorded history so stepping back also
+    /// rewinds the graph.
+    fn rebuild_replay_chart(&mut self) {
+        let Some(replay) = &self.replay else {
+            return;
+        };
+        let history = replay.history();
+        let start = history.len().saturating_sub(60);
+
+        self.chart_data = history[start..]
+            .iter()
+            .enumerate()
+            .map(|(i, update)| ((start + i) as f64 + 60.0, update.swap.used_swap as f64))
+            .collect();
+        self.time_window = [history.len() as f64, history.len() as f64 + 60.0];
+
+        #[cfg(target_os = "linux")]
+        {
+            let rates: Vec<(f64, f64, f64)> = history[start.max(1)..]
+                .iter()
+                .enumerate()
+                .map(|(i, update)| {
+                    let previous = &history[start.max(1) + i - 1];
+                    let seconds =
+                        update.timestamp_ms.saturating_sub(previous.timestamp_ms) as f64 / 1000.0;
+                    (
+                        (start.max(1) + i) as f64 + 60.0,
+                        swap_rate(
+                            previous.swap.swapped_in_kb,
+                            update.swap.swapped_in_kb,
+                            seconds,
+                        ),
+                        swap_rate(
+                            previous.swap.swapped_out_kb,
+                            update.swap.swapped_out_kb,
+                            seconds,
+                        ),
+                    )
+                })
+                .collect();
+            self.swap_in_data = rates.iter().map(|&(x, rate, _)| (x, rate)).collect();
+            self.swap_out_data = rates.iter().map(|&(x, _, rate)| (x, rate)).collect();
+
+            let pressure: Vec<(f64, &MemoryPressure)> = history[start..]
+                .iter()
+                .enumerate()
+                .filter_map(|(i, update)| {
+                    Some(((start + i) as f64 + 60.0, update.swap.pressure.as_ref()?))
+                })
+                .collect();
+            self.pressure_some_data = pressure
+                .iter()
+                .map(|(x, p)| (*x, p.some.avg10 as f64))
+                .collect();
+            self.pressure_full_data = pressure
+                .iter()
+                .map(|(x, p)| (*x, p.full.avg10 as f64))
+                .collect();
+            self.stall_rate = match &history[history.len().saturating_sub(2)..] {
+                [previous, current] => previous
+                    .swap
+                    .pressure
+                    .as_ref()
+                    .zip(current.swap.pressure.as_ref())
+                    .map(|(p, c)| {
+                        let seconds = current.timestamp_ms.saturating_sub(previous.timestamp_ms)
+                            as f64
+                            / 1000.0;
+                        stall_rate(p.some.total_us, c.some.total_us, seconds)
+                    }),
+                _ => None,
+            };
+        }
+    }
+
+    /// Handles the replay controls, returns false for keys that are not one of them.
+    fn on_replay_key(&mut self, code: KeyCode) -> bool {
+        let Some(replay) = &mut self.replay else {
+            return false;
+        };
+        match code {
+            KeyCode::Char(' ') => replay.toggle_pause(),
+            KeyCode::Right => {
+                if replay.step_forward() {
+                    self.on_replay_step();
+                }
+            }
+            KeyCode::Left => {
+                if replay.step_back() {
+                    self.on_replay_step();
+                }
+            }
+            KeyCode::Char('+') => replay.faster(),
+            KeyCode::Char('-') => replay.slower(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn interval_title(&self) -> String {
+        match &self.replay {
+            Some(replay) => format!(
+                " replay {}/{} x{} {}",
+                replay.position() + 1,
+                replay.update_count(),
+                replay.speed(),
+                if replay.paused() { "[paused] " } else { "" }
+            ),
+            None => format!(" < {:?}ms > ", self.timeout),
+        }
+    }
+
+    fn set_unit(&mut self, unit: SizeUnits) {
+        self.swap_size_unit = unit;
+        let _ = self.refresh_data();
+    }
+
+    #[cfg(target_os = "linux")]
+    fn zswap_title(&self) -> String {
+        let Some(zswap) = &self.chart_info.zswap else {
+            return String::new();
+        };
+        if !zswap.enabled && zswap.stored_size == 0 {
+            return String::new();
+        }
+
+        let size = |bytes: u64| self.format_kb(bytes / 1024);
+        let mut title = format!(
+            " zswap {}: pool {} | stored {} | {:.2}x",
+            zswap.compressor,
+            size(zswap.pool_size),
+            size(zswap.stored_size),
+            zswap.compression_ratio()
+        );
+        if let Some(written_back) = zswap.written_back_pages {
+            title.push_str(&format!(" | written back {written_back}"));
+        }
+        if let Some(rejected) = zswap.rejected_pages {
+            title.push_str(&format!(" | rejected {rejected}"));
+        }
+        title.push(' ');
+        title
+    }
+
+    #[cfg(target_os = "windows")]
+    fn zswap_title(&self) -> String {
+        String::new()
+    }
+
+    #[cfg(target_os = "linux")]
+    fn toggle_metric(&mut self) {
+        if self.replay.is_some() {
+            self.set_status("the swap metric is fixed by the recording");
+            return;
+        }
+        self.swap_metric = self.swap_metric.toggle();
+        let _ = self.refresh_data();
+    }
+
+    #[cfg(target_os = "linux")]
+    fn metric_title(&self) -> String {
+        format!(
+            " group by (x to change): {} | metric (p to toggle): {} ",
+            self.aggregate_by.label(),
+            self.swap_metric.label()
+        )
+    }
+
+    #[cfg(target_os = "windows")]
+    fn metric_title(&self) -> String {
+        format!(" group by (x to change): {} ", self.aggregate_by.label())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn key_hints(&self) -> &'static str {
+        "(a to aggregate) (f for tree) (o/r to sort) (enter for details) (K to signal) (b for mappings) (v for cgroups) (u/d|▲/▼|home/end|pgup/pgdown to select)"
+    }
+
+    #[cfg(target_os = "windows")]
+    fn key_hints(&self) -> &'static str {
+        "(a to aggregate) (o/r to sort) (u/d|▲/▼|home/end|pgup/pgdown to select)"
+    }
+
+    /// Unit and container columns, only shown for individual processes.
+    #[cfg(target_os = "linux")]
+    fn attribution_columns(&self) -> bool {
+        self.show_attribution && !self.aggregated && !self.tree_view
+    }
+
+    #[cfg(target_os = "windows")]
+    fn attribution_columns(&self) -> bool {
+        false
+    }
+
     #[cfg(target_os = "linux")]
     fn render(&mut self, frame: &mut Frame) {
         let theme = Theme::from(self.current_theme);
@@ -141,14 +841,28 @@ impl App {
                     .right_aligned(),
             )
             .title(
-                Line::from(format!(" < {:?}ms > ", self.timeout))
+                Line::from(self.interval_title())
                     .bold()
                     .fg(theme.primary)
                     .centered(),
             )
+            .title_bottom(self.status_line(&theme))
             .style(Style::default().bg(theme.background).fg(theme.text));
 
         let main_area = main_block.inner(frame.area());
+        frame.render_widget(main_block, frame.area());
+        let main_area = self.render_alert_banner(frame, main_area, &theme);
+
+        let main_area = if self.chart_info.pressure.is_some() {
+            let gauge_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(main_area);
+            self.render_pressure_gauge(frame, gauge_chunks[0], &theme);
+            gauge_chunks[1]
+        } else {
+            main_area
+        };
 
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -162,14 +876,45 @@ impl App {
                 .split(chunks[0]);
 
             self.render_animated_chart(frame, upper_chunks[1], &theme);
-            self.render_processes_list(frame, chunks[1], &theme);
-            self.render_swap_devices(frame, upper_chunks[0], &theme);
+
+            let zram_count = self.chart_info.zram_devices.len();
+            if zram_count > 0 {
+                let device_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Min(4),
+                        Constraint::Length(zram_count as u16 + 3),
+                    ])
+                    .split(upper_chunks[0]);
+
+                self.render_swap_devices(frame, device_chunks[0], &theme);
+                self.render_zram_devices(frame, device_chunks[1], &theme);
+            } else {
+                self.render_swap_devices(frame, upper_chunks[0], &theme);
+            }
         } else {
             self.render_animated_chart(frame, chunks[0], &theme);
+        }
+
+        if self.breakdown.is_some() {
+            self.render_swap_breakdown(frame, chunks[1], &theme);
+        } else if self.cgroup_tree.is_some() {
+            self.render_cgroup_tree(frame, chunks[1], &theme);
+        } else if self.detail.is_some() {
+            let lower_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .split(chunks[1]);
+
+            self.render_processes_list(frame, lower_chunks[0], &theme);
+            self.render_process_detail(frame, lower_chunks[1], &theme);
+        } else {
             self.render_processes_list(frame, chunks[1], &theme);
         }
 
-        frame.render_widget(main_block, frame.area());
+        self.render_signal_dialog(frame, chunks[1], &theme);
+        self.render_swap_in(frame, chunks[1], &theme);
+        self.render_device_manager(frame, frame.area(), &theme);
     }
 
     #[cfg(target_os = "windows")]
@@ -192,14 +937,17 @@ impl App {
                     .right_aligned(),
             )
             .title(
-                Line::from(format!(" < {:?}ms > ", self.timeout))
+                Line::from(self.interval_title())
                     .bold()
                     .fg(theme.primary)
                     .centered(),
             )
+            .title_bottom(self.status_line(&theme))
             .style(Style::default().bg(theme.background).fg(theme.text));
 
         let main_area = main_block.inner(frame.area());
+        frame.render_widget(main_block, frame.area());
+        let main_area = self.render_alert_banner(frame, main_area, &theme);
 
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -208,8 +956,6 @@ impl App {
 
         self.render_animated_chart(frame, chunks[0], &theme);
         self.render_processes_list(frame, chunks[1], &theme);
-
-        frame.render_widget(main_block, frame.area());
     }
 
     fn update_chart_data(&mut self) {
@@ -219,10 +965,68 @@ impl App {
         if self.chart_data.len() > 60 {
             self.chart_data.drain(0..1);
         }
+        #[cfg(target_os = "linux")]
+        self.update_pressure_data(timestamp);
+        #[cfg(target_os = "linux")]
+        self.update_io_data(timestamp);
         self.time_window[0] += 1.0;
         self.time_window[1] += 1.0;
     }
 
+    /// Appends the swap-in/out rates since the previous refresh.
+    #[cfg(target_os = "linux")]
+    fn update_io_data(&mut self, timestamp: f64) {
+        let current = (
+            self.chart_info.swapped_in_kb,
+            self.chart_info.swapped_out_kb,
+        );
+        if let Some((previous_in, previous_out)) = self.last_swap_io.replace(current)
+            && let Some(last_update) = self.last_update
+        {
+            let seconds = last_update.elapsed().as_secs_f64();
+            self.swap_in_data
+                .push((timestamp, swap_rate(previous_in, current.0, seconds)));
+            self.swap_out_data
+                .push((timestamp, swap_rate(previous_out, current.1, seconds)));
+            for data in [&mut self.swap_in_data, &mut self.swap_out_data] {
+                if data.len() > 60 {
+                    data.drain(0..1);
+                }
+            }
+        }
+    }
+
+    /// Appends the avg10 pressure and updates the stall time per second since the
+    /// previous refresh.
+    #[cfg(target_os = "linux")]
+    fn update_pressure_data(&mut self, timestamp: f64) {
+        let Some(pressure) = &self.chart_info.pressure else {
+            self.stall_rate = None;
+            return;
+        };
+
+        self.pressure_some_data
+            .push((timestamp, pressure.some.avg10 as f64));
+        self.pressure_full_data
+            .push((timestamp, pressure.full.avg10 as f64));
+        for data in [&mut self.pressure_some_data, &mut self.pressure_full_data] {
+            if data.len() > 60 {
+                data.drain(0..1);
+            }
+        }
+
+        let total = pressure.some.total_us;
+        if let Some(previous) = self.last_stall_us.replace(total)
+            && let Some(last_update) = self.last_update
+        {
+            self.stall_rate = Some(stall_rate(
+                previous,
+                total,
+                last_update.elapsed().as_secs_f64(),
+            ));
+        }
+    }
+
     fn handle_crossterm_events(&mut self) -> Result<()> {
         match event::read()? {
             Event::Key(key) if key.kind == KeyEventKind::Press => self.on_key_event(key),
@@ -235,88 +1039,94 @@ impl App {
 
     #[cfg(target_os = "linux")]
     fn on_key_event(&mut self, key: KeyEvent) {
-        if key.kind != KeyEventKind::Press {
+        if key.kind != KeyEventKind::Press
+            || self.on_signal_key(key)
+            || self.on_swap_in_key(key)
+            || self.on_device_key(key)
+            || self.on_filter_key(key)
+            || self.on_replay_key(key.code)
+            || self.on_breakdown_key(key.code)
+            || self.on_cgroup_key(key.code)
+        {
             return;
         }
 
         match key.code {
+            // close the detail pane, clear filter, then quit
+            KeyCode::Esc if self.detail.is_some() => self.detail = None,
+            KeyCode::Esc if self.filter.is_some() => self.clear_filter(),
+
             // quit
             KeyCode::Esc | KeyCode::Char('q') => self.quit(),
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit(),
 
-            // up and down list
-            KeyCode::Char('d') | KeyCode::Down => {
-                self.vertical_scroll = self.vertical_scroll.saturating_add(1);
-                self.vertical_scroll_state =
-                    self.vertical_scroll_state.position(self.vertical_scroll);
-            }
-            KeyCode::Char('u') | KeyCode::Up => {
-                self.vertical_scroll = self.vertical_scroll.saturating_sub(1);
-                self.vertical_scroll_state =
-                    self.vertical_scroll_state.position(self.vertical_scroll);
-            }
-            KeyCode::End => {
-                self.vertical_scroll = self.swap_processes_lines.len();
-                self.vertical_scroll_state =
-                    self.vertical_scroll_state.position(self.vertical_scroll);
-            }
-            KeyCode::Home => {
-                self.vertical_scroll = 0;
-                self.vertical_scroll_state =
-                    self.vertical_scroll_state.position(self.vertical_scroll);
-            }
-
-            KeyCode::PageDown => {
-                let page_size = self.visible_height.saturating_sub(4);
-                self.vertical_scroll = self
-                    .vertical_scroll
-                    .saturating_add(page_size)
-                    .min(self.swap_processes_lines.len().saturating_sub(1));
-                self.vertical_scroll_state =
-                    self.vertical_scroll_state.position(self.vertical_scroll);
+            // move the selection
+            KeyCode::Char('d') | KeyCode::Down => self.select(self.selection.saturating_add(1)),
+            KeyCode::Char('u') | KeyCode::Up => self.select(self.selection.saturating_sub(1)),
+            KeyCode::End => self.select(self.process_rows.len().saturating_sub(1)),
+            KeyCode::Home => self.select(0),
+
+            KeyCode::PageDown => {
+                let page_size = self.visible_height.saturating_sub(4);
+                self.select(self.selection.saturating_add(page_size));
             }
             KeyCode::PageUp => {
                 let page_size = self.visible_height.saturating_sub(4);
-                self.vertical_scroll = self.vertical_scroll.saturating_sub(page_size);
-                self.vertical_scroll_state =
-                    self.vertical_scroll_state.position(self.vertical_scroll);
+                self.select(self.selection.saturating_sub(page_size));
             }
 
             // change unit
-            KeyCode::Char('k') => {
-                self.swap_size_unit = SizeUnits::KB;
-                if let Ok(info) = get_chart_info(self.swap_size_unit.clone()) {
-                    self.chart_info = info;
-                    self.swap_processes_lines = self.create_process_lines(self.aggregated);
-                }
+            KeyCode::Char('k') => self.set_unit(SizeUnits::KB),
+            KeyCode::Char('m') => self.set_unit(SizeUnits::MB),
+            KeyCode::Char('g') => self.set_unit(SizeUnits::GB),
+
+            // aggregate
+            KeyCode::Char('a') => {
+                self.aggregated = !self.aggregated;
+                self.rebuild_process_lines();
             }
-            KeyCode::Char('m') => {
-                self.swap_size_unit = SizeUnits::MB;
-                if let Ok(info) = get_chart_info(self.swap_size_unit.clone()) {
-                    self.chart_info = info;
-                    self.swap_processes_lines = self.create_process_lines(self.aggregated);
-                }
+
+            // VmSwap or SwapPss
+            KeyCode::Char('p') => self.toggle_metric(),
+
+            // aggregation key and cgroup tree
+            KeyCode::Char('x') => {
+                self.aggregate_by = self.aggregate_by.next();
+                self.rebuild_process_lines();
             }
-            KeyCode::Char('g') => {
-                self.swap_size_unit = SizeUnits::GB;
-                if let Ok(info) = get_chart_info(self.swap_size_unit.clone()) {
-                    self.chart_info = info;
-                    self.swap_processes_lines = self.create_process_lines(self.aggregated);
-                }
+            KeyCode::Char('v') => self.open_cgroup_tree(),
+            KeyCode::Char('f') => self.toggle_tree(),
+            KeyCode::Char('K') => self.open_signal_dialog(),
+            KeyCode::Char('i') => self.open_swap_in(),
+            KeyCode::Char('w') => self.open_device_manager(),
+            KeyCode::Char(' ') => self.toggle_collapsed(),
+            KeyCode::Char('e') => {
+                self.show_attribution = !self.show_attribution;
+                self.rebuild_process_lines();
             }
 
-            // aggregate
-            KeyCode::Char('a') => self.aggregated = !self.aggregated,
+            // swap usage or swap I/O chart
+            KeyCode::Char('c') => self.chart_mode = self.chart_mode.next(),
+
+            // process details
+            KeyCode::Enter => self.toggle_detail(),
+            KeyCode::Char('b') => self.open_breakdown(),
+
+            // filter
+            KeyCode::Char('/') => self.filter_editing = true,
+
+            // sort
+            KeyCode::Char('o') => self.cycle_sort_column(),
+            KeyCode::Char('r') => self.reverse_sort(),
 
             // change theme
             KeyCode::Char('t') => self.cycle_theme(),
 
+            // save settings
+            KeyCode::Char('s') => self.save_settings(),
+
             // display swap devices
-            KeyCode::Char('h') => {
-                if LINUX {
-                    self.display_devices = !self.display_devices
-                }
-            }
+            KeyCode::Char('h') if LINUX => self.display_devices = !self.display_devices,
 
             // change timeout
             KeyCode::Left | KeyCode::Right => self.change_timout(key.code),
@@ -327,88 +1137,66 @@ impl App {
 
     #[cfg(target_os = "windows")]
     fn on_key_event(&mut self, key: KeyEvent) {
-        if key.kind != KeyEventKind::Press {
+        if key.kind != KeyEventKind::Press
+            || self.on_filter_key(key)
+            || self.on_replay_key(key.code)
+        {
             return;
         }
 
         match key.code {
+            // clear filter, then quit
+            KeyCode::Esc if self.filter.is_some() => self.clear_filter(),
+
             // quit
             KeyCode::Esc | KeyCode::Char('q') => self.quit(),
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit(),
 
-            // up and down list
-            KeyCode::Char('d') | KeyCode::Down => {
-                self.vertical_scroll = self.vertical_scroll.saturating_add(1);
-                self.vertical_scroll_state =
-                    self.vertical_scroll_state.position(self.vertical_scroll);
-            }
-            KeyCode::Char('u') | KeyCode::Up => {
-                self.vertical_scroll = self.vertical_scroll.saturating_sub(1);
-                self.vertical_scroll_state =
-                    self.vertical_scroll_state.position(self.vertical_scroll);
-            }
-            KeyCode::End => {
-                self.vertical_scroll = self.swap_processes_lines.len();
-                self.vertical_scroll_state =
-                    self.vertical_scroll_state.position(self.vertical_scroll);
-            }
-            KeyCode::Home => {
-                self.vertical_scroll = 0;
-                self.vertical_scroll_state =
-                    self.vertical_scroll_state.position(self.vertical_scroll);
-            }
+            // move the selection
+            KeyCode::Char('d') | KeyCode::Down => self.select(self.selection.saturating_add(1)),
+            KeyCode::Char('u') | KeyCode::Up => self.select(self.selection.saturating_sub(1)),
+            KeyCode::End => self.select(self.process_rows.len().saturating_sub(1)),
+            KeyCode::Home => self.select(0),
 
             KeyCode::PageDown => {
                 let page_size = self.visible_height.saturating_sub(4);
-                self.vertical_scroll = self
-                    .vertical_scroll
-                    .saturating_add(page_size)
-                    .min(self.swap_processes_lines.len().saturating_sub(1));
-                self.vertical_scroll_state =
-                    self.vertical_scroll_state.position(self.vertical_scroll);
+                self.select(self.selection.saturating_add(page_size));
             }
             KeyCode::PageUp => {
                 let page_size = self.visible_height.saturating_sub(4);
-                self.vertical_scroll = self.vertical_scroll.saturating_sub(page_size);
-                self.vertical_scroll_state =
-                    self.vertical_scroll_state.position(self.vertical_scroll);
+                self.select(self.selection.saturating_sub(page_size));
             }
 
             // change unit
-            KeyCode::Char('k') => {
-                self.swap_size_unit = SizeUnits::KB;
-                if let Ok(info) = get_chart_info() {
-                    self.chart_info = info;
-                    self.swap_processes_lines = self.create_process_lines(self.aggregated);
-                }
-            }
-            KeyCode::Char('m') => {
-                self.swap_size_unit = SizeUnits::MB;
-                if let Ok(info) = get_chart_info() {
-                    self.chart_info = info;
-                    self.swap_processes_lines = self.create_process_lines(self.aggregated);
-                }
+            KeyCode::Char('k') => self.set_unit(SizeUnits::KB),
+            KeyCode::Char('m') => self.set_unit(SizeUnits::MB),
+            KeyCode::Char('g') => self.set_unit(SizeUnits::GB),
+
+            // aggregate
+            KeyCode::Char('a') => {
+                self.aggregated = !self.aggregated;
+                self.rebuild_process_lines();
             }
-            KeyCode::Char('g') => {
-                self.swap_size_unit = SizeUnits::GB;
-                if let Ok(info) = get_chart_info() {
-                    self.chart_info = info;
-                    self.swap_processes_lines = self.create_process_lines(self.aggregated);
-                }
+            KeyCode::Char('x') => {
+                self.aggregate_by = self.aggregate_by.next();
+                self.rebuild_process_lines();
             }
 
-            // aggregate
-            KeyCode::Char('a') => self.aggregated = !self.aggregated,
+            // filter
+            KeyCode::Char('/') => self.filter_editing = true,
+
+            // sort
+            KeyCode::Char('o') => self.cycle_sort_column(),
+            KeyCode::Char('r') => self.reverse_sort(),
 
             // change theme
             KeyCode::Char('t') => self.cycle_theme(),
 
+            // save settings
+            KeyCode::Char('s') => self.save_settings(),
+
             // display swap devices
-            KeyCode::Char('h') => {
-                if LINUX {
-                    self.display_devices = !self.display_devices
-                }
-            }
+            KeyCode::Char('h') if LINUX => self.display_devices = !self.display_devices,
 
             // change timeout
             KeyCode::Left | KeyCode::Right => self.change_timout(key.code),
@@ -425,202 +1213,1628 @@ impl App {
             ThemeType::Dracula => ThemeType::Nord,
             ThemeType::Nord => ThemeType::Default,
         };
-        self.swap_processes_lines = self.create_process_lines(self.aggregated);
+        self.rebuild_process_lines();
     }
 
-    fn change_timout(&mut self, action: KeyCode) {
-        match action {
-            KeyCode::Left => {
-                self.timeout = self.timeout.saturating_sub(100).max(1);
+    /// Handles typing in the filter prompt, returns false when it is not open.
+    fn on_filter_key(&mut self, key: KeyEvent) -> bool {
+        if !self.filter_editing {
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit(),
+            KeyCode::Esc => self.clear_filter(),
+            KeyCode::Enter => self.filter_editing = false,
+            KeyCode::Backspace => {
+                self.filter_input.pop();
+                self.update_filter();
             }
-            KeyCode::Right => {
-                self.timeout = self.timeout.saturating_add(100).min(10000);
+            KeyCode::Char(c) => {
+                self.filter_input.push(c);
+                self.update_filter();
             }
             _ => {}
         }
-    }
-    fn quit(&mut self) {
-        self.running = false;
+        true
     }
 
-    fn create_process_lines(&self, aggregated: bool) -> Vec<Line<'static>> {
-        let mut lines = Vec::new();
+    fn update_filter(&mut self) {
+        self.filter = ProcessFilter::new(&self.filter_input);
+        self.vertical_scroll = 0;
+        self.rebuild_process_lines();
+    }
 
-        lines.push(Line::from(vec![
-            format!("{:>12}", if self.aggregated { "COUNT" } else { "PID" }).bold(),
-            " | ".into(),
-            format!("{:30}", "PROCESS").bold(),
-            " | ".into(),
-            format!("{:10}", "USED").bold(),
-        ]));
-
-        if let Ok(mut processes) = get_processes_using_swap(self.swap_size_unit.clone()) {
-            processes.sort_by(|a, b| {
-                b.swap_size
-                    .partial_cmp(&a.swap_size)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
+    fn clear_filter(&mut self) {
+        self.filter_input.clear();
+        self.filter_editing = false;
+        self.update_filter();
+    }
 
-            if aggregated {
-                processes = aggregate_processes(processes);
-            }
+    fn filter_title(&self) -> String {
+        let matches = self.swap_processes_lines.len().saturating_sub(1);
+        if self.filter_editing {
+            format!("/{}█ ({} matches)", self.filter_input, matches)
+        } else if self.filter.is_some() {
+            format!(
+                "filter: {} ({} matches, esc to clear)",
+                self.filter_input, matches
+            )
+        } else {
+            "(/ to filter)".to_owned()
+        }
+    }
 
-            for process in processes {
-                let mut process_size: String = format!("{:.2}", process.swap_size);
-                if let SizeUnits::KB = self.swap_size_unit {
-                    process_size = format!("{}", process.swap_size)
-                }
+    fn row_key(&self, process: &ProcessSwapInfo) -> String {
+        if self.aggregated {
+            process.name.clone()
+        } else {
+            process.pid.to_string()
+        }
+    }
 
-                lines.push(Line::from(vec![
-                    format!("{:12}", process.pid).into(),
-                    " | ".into(),
-                    format!("{:30}", process.name).into(),
-                    " | ".into(),
-                    format!("{:10}", process_size).into(),
-                ]));
-            }
+    /// Rebuilds the visible rows and keeps the cursor on the same process (or
+    /// group) when sorting, filtering or a refresh moves it around.
+    fn rebuild_process_lines(&mut self) {
+        // hiding the unit and container columns drops a sort by them
+        if self.sort_column.is_attribution() && !self.attribution_columns() {
+            self.sort_column = SortColumn::Swap;
+            self.sort_descending = true;
+        }
+        if !self.rebuild_tree_lines() {
+            self.process_rows = self.visible_processes(self.aggregated);
+            self.swap_processes_lines = self.create_process_lines(&self.process_rows);
         }
 
-        lines
+        let position = self.selected_key.as_ref().and_then(|key| {
+            self.process_rows
+                .iter()
+                .position(|process| &self.row_key(process) == key)
+        });
+        match position {
+            Some(index) => self.selection = index,
+            None => self.select(self.selection),
+        }
     }
 
-    fn generete_total_used_title(&mut self) -> String {
-        let total = convert_swap(self.chart_info.total_swap, self.swap_size_unit.clone());
-        let used = convert_swap(self.chart_info.used_swap, self.swap_size_unit.clone());
+    /// The tree replaces the flat list for individual processes only.
+    #[cfg(target_os = "linux")]
+    fn tree_active(&self) -> bool {
+        self.tree_view && !self.aggregated
+    }
 
-        let total_used_title: String = match self.swap_size_unit {
-            SizeUnits::KB => format!("total: {} | used: {}", total, used),
-            SizeUnits::MB => format!("total: {} | used: {:.2}", total.round(), used),
-            SizeUnits::GB => format!("total: {:.2} | used: {:.2}", total, used),
-        };
+    #[cfg(target_os = "linux")]
+    fn rebuild_tree_lines(&mut self) -> bool {
+        if !self.tree_active() {
+            return false;
+        }
+        let mut processes = self.processes.clone();
+        if let Some(filter) = &self.filter {
+            processes.retain(|p| filter.matches(p));
+        }
+        self.tree_rows = ProcessTree::build(&processes).rows(
+            &self.collapsed,
+            self.sort_column,
+            self.sort_descending,
+        );
+        self.process_rows = self
+            .tree_rows
+            .iter()
+            .map(|row| row.process.clone())
+            .collect();
+        self.swap_processes_lines = self.create_tree_lines();
+        true
+    }
 
-        total_used_title
+    #[cfg(target_os = "windows")]
+    fn rebuild_tree_lines(&mut self) -> bool {
+        false
     }
 
     #[cfg(target_os = "linux")]
-    fn render_swap_devices(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
-        let total_used_title = self.generete_total_used_title();
+    fn toggle_tree(&mut self) {
+        if self.replay.is_some() {
+            self.set_status("the process tree is not recorded");
+            return;
+        }
+        self.tree_view = !self.tree_view;
+        if self.tree_view {
+            self.aggregated = false;
+        }
+        self.rebuild_process_lines();
+    }
 
-        let total_n_used_line = if !self.display_devices {
-            Line::from("").fg(theme.text).left_aligned()
-        } else {
-            Line::from(total_used_title).fg(theme.text).left_aligned()
+    /// Expands or collapses the selected tree node.
+    #[cfg(target_os = "linux")]
+    fn toggle_collapsed(&mut self) {
+        let Some(row) = self.tree_rows.get(self.selection) else {
+            return;
         };
+        if !self.tree_active() || !row.has_children {
+            return;
+        }
+        let pid = row.process.pid;
+        if !self.collapsed.remove(&pid) {
+            self.collapsed.insert(pid);
+        }
+        self.rebuild_process_lines();
+    }
 
-        let name_width = self
-            .chart_info
-            .swap_devices
-            .iter()
-            .map(|d| d.name.len())
-            .max()
-            .unwrap_or(10)
-            .max(10);
-
-        let source_width = self
-            .chart_info
-            .swap_devices
-            .iter()
-            .map(|d| {
-                let src = find_mount_device(std::path::Path::new(&d.name))
-                    .unwrap_or_else(|| "RAM".into());
-                src.len()
-            })
-            .max()
-            .unwrap_or(4)
-            .max(4);
+    fn select(&mut self, index: usize) {
+        self.selection = index.min(self.process_rows.len().saturating_sub(1));
+        self.selected_key = self
+            .process_rows
+            .get(self.selection)
+            .map(|process| self.row_key(process));
+
+        #[cfg(target_os = "linux")]
+        if let Some(detail) = &self.detail
+            && let Some(process) = self.selected_process()
+            && detail.pid != process.pid
+        {
+            self.open_detail();
+        }
+    }
 
-        let wide = area.width >= 80;
-        let mut lines = Vec::new();
+    #[cfg(target_os = "linux")]
+    fn selected_process(&self) -> Option<&ProcessSwapInfo> {
+        if self.aggregated {
+            return None;
+        }
+        self.process_rows.get(self.selection)
+    }
 
-        if wide {
-            lines.push(Line::from(format!(
-                "{:<source_width$} | {:<name_width$} | {:<10} | {:>8} | {:>10} | {:>10}",
-                "disk", "path", "type", "priority", "total", "used"
-            )));
+    #[cfg(target_os = "linux")]
+    fn toggle_detail(&mut self) {
+        if self.detail.is_some() {
+            self.detail = None;
         } else {
-            lines.push(Line::from(format!(
-                "{:<source_width$} | {:<name_width$} | {:<10} | {:>10}",
-                "disk", "path", "total", "used"
-            )));
+            self.open_detail();
         }
+    }
 
-        for device in &self.chart_info.swap_devices {
-            let used = match self.swap_size_unit {
-                SizeUnits::KB => device.used_kb.to_string(),
-                _ => format!("{:.2}", device.used_kb),
-            };
+    #[cfg(target_os = "linux")]
+    fn open_detail(&mut self) {
+        if self.replay.is_some() {
+            self.set_status("process details are not available while replaying");
+            return;
+        }
+        let Some(pid) = self.selected_process().map(|p| p.pid) else {
+            self.set_status("select a single process to see its details");
+            return;
+        };
+        match ProcessDetail::read(pid) {
+            Ok(detail) => self.detail = Some(detail),
+            Err(e) => {
+                self.detail = None;
+                self.set_status(format!("could not read process {pid}: {e}"));
+            }
+        }
+    }
 
-            let source = find_mount_device(std::path::Path::new(&device.name))
-                .unwrap_or_else(|| "RAM".into());
+    #[cfg(target_os = "linux")]
+    fn refresh_detail(&mut self) {
+        let Some(detail) = &mut self.detail else {
+            return;
+        };
+        if let Err(e) = detail.refresh() {
+            let pid = detail.pid;
+            self.detail = None;
+            self.set_status(format!("process {pid} is gone: {e}"));
+        }
+    }
 
-            let total = match self.swap_size_unit {
-                SizeUnits::KB => device.size_kb.to_string(),
-                _ => format!("{:.2}", device.size_kb),
-            };
+    #[cfg(target_os = "linux")]
+    fn open_breakdown(&mut self) {
+        if self.replay.is_some() {
+            self.set_status("the mapping breakdown is not available while replaying");
+            return;
+        }
+        let Some(pid) = self.selected_process().map(|p| p.pid) else {
+            self.set_status("select a single process to see its mappings");
+            return;
+        };
+        match SwapBreakdown::read(pid) {
+            Ok(breakdown) => {
+                self.breakdown = Some(breakdown);
+                self.breakdown_scroll = 0;
+            }
+            Err(e) => self.set_status(format!("could not read the mappings of {pid}: {e}")),
+        }
+    }
 
-            let row = if wide {
-                format!(
-                    "{:<source_width$} | {:<name_width$} | {:<10} | {:>8} | {:>10} | {:>10}",
-                    source, device.name, device.kind, device.priority, total, used
-                )
-            } else {
-                format!(
-                    "{:<source_width$} | {:<name_width$} | {:<10} | {:>10}",
-                    source, device.name, total, used
-                )
-            };
-            lines.push(Line::from(row));
+    #[cfg(target_os = "linux")]
+    fn refresh_breakdown(&mut self) {
+        let Some(breakdown) = &self.breakdown else {
+            return;
+        };
+        match SwapBreakdown::read(breakdown.pid) {
+            Ok(breakdown) => {
+                self.breakdown_scroll = self
+                    .breakdown_scroll
+                    .min(breakdown.mappings.len().saturating_sub(1));
+                self.breakdown = Some(breakdown);
+            }
+            Err(e) => {
+                let pid = breakdown.pid;
+                self.breakdown = None;
+                self.set_status(format!("process {pid} is gone: {e}"));
+            }
         }
+    }
 
-        let block = Block::bordered()
-            .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(theme.border))
-            .style(Style::default().bg(theme.background))
-            .title(total_n_used_line.right_aligned())
-            .title(Line::from("swap devices").fg(theme.text).left_aligned())
-            .title_bottom(Line::from("(h to hide swap devices)").left_aligned());
+    /// Scrolls the mapping breakdown while it is open. Keys it does not use
+    /// fall through to the main handler.
+    #[cfg(target_os = "linux")]
+    fn on_breakdown_key(&mut self, code: KeyCode) -> bool {
+        let Some(breakdown) = &self.breakdown else {
+            return false;
+        };
+        let last = breakdown.mappings.len().saturating_sub(1);
+        let page_size = self.visible_height.saturating_sub(4).max(1);
 
-        let para = Paragraph::new(lines).block(block).centered();
-        frame.render_widget(para, area);
+        match code {
+            KeyCode::Esc | KeyCode::Char('b') => self.breakdown = None,
+            KeyCode::Char('d') | KeyCode::Down => {
+                self.breakdown_scroll = (self.breakdown_scroll + 1).min(last)
+            }
+            KeyCode::Char('u') | KeyCode::Up => {
+                self.breakdown_scroll = self.breakdown_scroll.saturating_sub(1)
+            }
+            KeyCode::PageDown => {
+                self.breakdown_scroll = (self.breakdown_scroll + page_size).min(last)
+            }
+            KeyCode::PageUp => {
+                self.breakdown_scroll = self.breakdown_scroll.saturating_sub(page_size)
+            }
+            KeyCode::Home => self.breakdown_scroll = 0,
+            KeyCode::End => self.breakdown_scroll = last,
+            _ => return false,
+        }
+        true
     }
 
-    fn render_animated_chart(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
-        let total_used_title = self.generete_total_used_title();
+    #[cfg(target_os = "linux")]
+    fn open_cgroup_tree(&mut self) {
+        if self.replay.is_some() {
+            self.set_status("the cgroup tree is not available while replaying");
+            return;
+        }
+        match get_cgroup_swap() {
+            Some(tree) => {
+                self.cgroup_tree = Some(tree);
+                self.cgroup_scroll = 0;
+            }
+            None => self.set_status("no cgroup v2 hierarchy with the memory controller is mounted"),
+        }
+    }
 
-        let total_n_used_line = if self.display_devices {
-            Line::from("").fg(theme.text).left_aligned()
-        } else {
-            Line::from(total_used_title).fg(theme.text).left_aligned()
+    /// Scrolls the cgroup tree while it is open, like `on_breakdown_key`.
+    #[cfg(target_os = "linux")]
+    fn on_cgroup_key(&mut self, code: KeyCode) -> bool {
+        let Some(tree) = &self.cgroup_tree else {
+            return false;
         };
+        let last = tree.len().saturating_sub(1);
+        let page_size = self.visible_height.saturating_sub(4).max(1);
 
-        let swap_usage_percent =
-            self.chart_info.used_swap as f64 / self.chart_info.total_swap as f64 * 100.0;
-        let datasets = vec![
-            Dataset::default()
-                .marker(Marker::Braille)
-                .style(Style::default().fg(theme.primary))
-                .graph_type(GraphType::Line)
-                .data(&self.chart_data),
-        ];
+        match code {
+            KeyCode::Esc | KeyCode::Char('v') => self.cgroup_tree = None,
+            KeyCode::Char('d') | KeyCode::Down => {
+                self.cgroup_scroll = (self.cgroup_scroll + 1).min(last)
+            }
+            KeyCode::Char('u') | KeyCode::Up => {
+                self.cgroup_scroll = self.cgroup_scroll.saturating_sub(1)
+            }
+            KeyCode::PageDown => self.cgroup_scroll = (self.cgroup_scroll + page_size).min(last),
+            KeyCode::PageUp => self.cgroup_scroll = self.cgroup_scroll.saturating_sub(page_size),
+            KeyCode::Home => self.cgroup_scroll = 0,
+            KeyCode::End => self.cgroup_scroll = last,
+            _ => return false,
+        }
+        true
+    }
 
-        let bottom_title = if LINUX && !self.display_devices {
-            "(h to show swap devices)"
+    /// Opens the signal picker for the selected process, or for every process
+    /// of the selected group in aggregate mode.
+    #[cfg(target_os = "linux")]
+    fn open_signal_dialog(&mut self) {
+        if self.replay.is_some() {
+            self.set_status("signals cannot be sent while replaying a recording");
+            return;
+        }
+        let Some(row) = self.process_rows.get(self.selection) else {
+            return;
+        };
+
+        let dialog = if self.aggregated {
+            let targets: Vec<(u32, String)> = self
+                .processes
+                .iter()
+                .filter(|p| self.filter.as_ref().is_none_or(|f| f.matches(p)))
+                .filter(|p| self.aggregate_by.key(p) == row.name)
+                .map(|p| (p.pid, p.name.clone()))
+                .collect();
+            SignalDialog::new(
+                format!(
+                    "{} {} ({} processes)",
+                    self.aggregate_by.label(),
+                    row.name,
+                    targets.len()
+                ),
+                targets,
+            )
         } else {
-            ""
+            SignalDialog::new(
+                format!("PID {} ({})", row.pid, row.name),
+                vec![(row.pid, row.name.clone())],
+            )
         };
-        let chart = Chart::new(datasets)
-            .block(
-                Block::bordered()
+        self.signal_dialog = Some(dialog);
+    }
+
+    /// Takes every key while the signal picker is open: pick a signal, confirm
+    /// with `y`, back out with `n` or `Esc`.
+    #[cfg(target_os = "linux")]
+    fn on_signal_key(&mut self, key: KeyEvent) -> bool {
+        let Some(dialog) = &mut self.signal_dialog else {
+            return false;
+        };
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.quit();
+            return true;
+        }
+        let code = key.code;
+
+        if dialog.confirming {
+            match code {
+                KeyCode::Char('y') => {
+                    dialog.confirming = false;
+                    let sent = dialog.send();
+                    if dialog.error.is_none() {
+                        let message = format!(
+                            "sent {} to {} process{}",
+                            dialog.signal().name(),
+                            sent,
+                            if sent == 1 { "" } else { "es" }
+                        );
+                        self.signal_dialog = None;
+                        self.set_status(message);
+                        let _ = self.refresh_data();
+                    }
+                }
+                KeyCode::Char('n') | KeyCode::Esc => dialog.confirming = false,
+                _ => {}
+            }
+            return true;
+        }
+
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => self.signal_dialog = None,
+            KeyCode::Char('d') | KeyCode::Down => {
+                dialog.selected = (dialog.selected + 1).min(Signal::ALL.len() - 1)
+            }
+            KeyCode::Char('u') | KeyCode::Up => dialog.selected = dialog.selected.saturating_sub(1),
+            KeyCode::Enter if !dialog.targets.is_empty() => {
+                dialog.confirming = true;
+                dialog.error = None;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Plans a swap-in of the selected process and shows the estimate.
+    #[cfg(target_os = "linux")]
+    fn open_swap_in(&mut self) {
+        if self.replay.is_some() {
+            self.set_status("processes cannot be swapped in while replaying a recording");
+            return;
+        }
+        let Some(process) = self.selected_process() else {
+            self.set_status("select a single process to swap in");
+            return;
+        };
+        let pid = process.pid;
+        match SwapIn::plan(pid) {
+            Ok(swap_in) => self.swap_in = Some(swap_in),
+            Err(e) => self.set_status(format!("cannot swap in {pid}: {e}")),
+        }
+    }
+
+    /// Takes every key while the swap-in dialog is open. `Enter` starts once the
+    /// pages fit in MemAvailable, `Esc` closes or cancels.
+    #[cfg(target_os = "linux")]
+    fn on_swap_in_key(&mut self, key: KeyEvent) -> bool {
+        let Some(swap_in) = &mut self.swap_in else {
+            return false;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit(),
+            KeyCode::Esc | KeyCode::Char('q') => {
+                let cancelled = swap_in.started.then(|| (swap_in.pid, swap_in.done_bytes()));
+                self.swap_in = None;
+                if let Some((pid, done)) = cancelled {
+                    let message = format!(
+                        "swap-in of {pid} cancelled after {}",
+                        self.format_kb(done / 1024)
+                    );
+                    self.set_status(message);
+                }
+            }
+            KeyCode::Enter | KeyCode::Char('y') if !swap_in.started && swap_in.fits() => {
+                swap_in.started = true
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Faults in the next chunk of a running swap-in and reports when it ends.
+    #[cfg(target_os = "linux")]
+    fn step_swap_in(&mut self) {
+        let Some(swap_in) = &mut self.swap_in else {
+            return;
+        };
+        if !swap_in.started {
+            return;
+        }
+        if let Err(e) = swap_in.step(swapin::STEP_BYTES) {
+            let message = format!("swap-in of {} failed: {e}", swap_in.pid);
+            self.swap_in = None;
+            self.set_status(message);
+            return;
+        }
+        if let Some(swap_in) = self.swap_in.take_if(|s| s.is_done()) {
+            let message = format!(
+                "swapped in {} of {} ({}), {} no longer mapped",
+                self.format_kb(swap_in.done_bytes() / 1024),
+                swap_in.name,
+                swap_in.pid,
+                self.format_kb(swap_in.skipped_bytes() / 1024)
+            );
+            self.set_status(message);
+            let _ = self.refresh_data();
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn open_device_manager(&mut self) {
+        if self.replay.is_some() {
+            self.set_status("swap devices cannot be managed while replaying a recording");
+            return;
+        }
+        match DeviceManager::open() {
+            Ok(manager) => self.device_manager = Some(manager),
+            Err(e) => self.set_status(format!("cannot read swap devices: {e}")),
+        }
+    }
+
+    /// Takes every key while the device manager is open. `Enter` turns the
+    /// selected area off or on, `p` types a new priority, every action waits
+    /// for `y`.
+    #[cfg(target_os = "linux")]
+    fn on_device_key(&mut self, key: KeyEvent) -> bool {
+        let Some(manager) = &mut self.device_manager else {
+            return false;
+        };
+        let code = key.code;
+        if manager.running.is_some() {
+            let ctrl_c =
+                code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+            if ctrl_c || code == KeyCode::Char('q') {
+                self.quit();
+            }
+            return true;
+        }
+
+        if manager.confirm.is_some() {
+            match code {
+                KeyCode::Char('y') => manager.start(),
+                KeyCode::Char('n') | KeyCode::Esc => manager.confirm = None,
+                _ => {}
+            }
+            return true;
+        }
+
+        if let Some(form) = &mut manager.new_file {
+            match code {
+                KeyCode::Esc => manager.new_file = None,
+                KeyCode::Tab | KeyCode::Down => form.field = (form.field + 1) % 3,
+                KeyCode::BackTab | KeyCode::Up => form.field = (form.field + 2) % 3,
+                KeyCode::Backspace => {
+                    form.input().pop();
+                }
+                KeyCode::Char(c) => form.input().push(c),
+                KeyCode::Enter => match form.action() {
+                    Ok(confirm) => {
+                        manager.confirm = Some(confirm);
+                        manager.new_file = None;
+                        manager.error = None;
+                    }
+                    Err(e) => manager.error = Some(e),
+                },
+                _ => {}
+            }
+            return true;
+        }
+
+        if let Some(input) = &mut manager.priority_input {
+            match code {
+                KeyCode::Char(c) if c.is_ascii_digit() && input.len() < 5 => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Esc => manager.priority_input = None,
+                KeyCode::Enter => match input.parse::<i32>() {
+                    Ok(priority) if (0..=MAX_PRIORITY).contains(&priority) => {
+                        let active = manager.selected_entry().is_some_and(|e| e.active);
+                        manager.confirm_selected(if active {
+                            DeviceAction::SetPriority(priority)
+                        } else {
+                            DeviceAction::SwapOn(Some(priority))
+                        });
+                        manager.priority_input = None;
+                    }
+                    _ => manager.error = Some(format!("priority must be 0-{MAX_PRIORITY}")),
+                },
+                _ => {}
+            }
+            return true;
+        }
+
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('w') => self.device_manager = None,
+            KeyCode::Char('d') | KeyCode::Down => {
+                manager.selected =
+                    (manager.selected + 1).min(manager.entries.len().saturating_sub(1))
+            }
+            KeyCode::Char('u') | KeyCode::Up => {
+                manager.selected = manager.selected.saturating_sub(1)
+            }
+            KeyCode::Home => manager.selected = 0,
+            KeyCode::End => manager.selected = manager.entries.len().saturating_sub(1),
+            KeyCode::Enter => {
+                if let Some(entry) = manager.selected_entry() {
+                    let action = if entry.active {
+                        DeviceAction::SwapOff
+                    } else {
+                        DeviceAction::SwapOn(entry.priority)
+                    };
+                    manager.confirm_selected(action);
+                }
+            }
+            KeyCode::Char('p') if manager.selected_entry().is_some() => {
+                manager.priority_input = Some(String::new());
+                manager.error = None;
+            }
+            KeyCode::Char('n') => {
+                manager.new_file = Some(NewSwapFile::default());
+                manager.error = None;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Reports a finished swapon/swapoff and rereads the devices.
+    #[cfg(target_os = "linux")]
+    fn poll_device_action(&mut self) {
+        let Some(manager) = &mut self.device_manager else {
+            return;
+        };
+        let Some((description, result)) = manager.finish() else {
+            return;
+        };
+        let _ = manager.reload();
+        // a failed action is shown instead of quitting over it
+        if std::mem::take(&mut self.quit_pending) && result.is_ok() {
+            self.running = false;
+        }
+        match result {
+            Ok(()) => {
+                self.set_status(format!("{description}: done"));
+                let _ = self.refresh_data();
+            }
+            Err(e) => manager.error = Some(e.to_string()),
+        }
+    }
+
+    fn cycle_sort_column(&mut self) {
+        self.sort_column = self.sort_column.next();
+        while self.sort_column.is_attribution() && !self.attribution_columns() {
+            self.sort_column = self.sort_column.next();
+        }
+        self.sort_descending = self.sort_column.default_descending();
+        self.rebuild_process_lines();
+    }
+
+    fn reverse_sort(&mut self) {
+        self.sort_descending = !self.sort_descending;
+        self.rebuild_process_lines();
+    }
+
+    fn sort_header(&self, column: SortColumn, label: &str) -> String {
+        if self.sort_column != column {
+            return label.to_owned();
+        }
+        format!("{} {}", label, if self.sort_descending { "▼" } else { "▲" })
+    }
+
+    fn change_timout(&mut self, action: KeyCode) {
+        match action {
+            KeyCode::Left => {
+                self.timeout = self.timeout.saturating_sub(100).max(1);
+            }
+            KeyCode::Right => {
+                self.timeout = self.timeout.saturating_add(100).min(10000);
+            }
+            _ => {}
+        }
+    }
+    /// Stops the main loop. A running swap device action is waited for, turning
+    /// an area off and not back on would lose it, a second quit leaves it behind.
+    fn quit(&mut self) {
+        #[cfg(target_os = "linux")]
+        if !self.quit_pending
+            && let Some((description, _)) = self
+                .device_manager
+                .as_ref()
+                .and_then(|manager| manager.running.as_ref())
+        {
+            let message = format!("quitting once {description} finishes, quit again to leave now");
+            self.quit_pending = true;
+            self.set_status(message);
+            return;
+        }
+        self.running = false;
+    }
+
+    fn visible_processes(&self, aggregated: bool) -> Vec<ProcessSwapInfo> {
+        let mut processes = self.processes.clone();
+        if let Some(filter) = &self.filter {
+            processes.retain(|p| filter.matches(p));
+        }
+        if aggregated {
+            processes = aggregate_processes(processes, self.aggregate_by);
+        }
+        sort_processes(&mut processes, self.sort_column, self.sort_descending);
+        processes
+    }
+
+    fn name_column(&self) -> &'static str {
+        self.group_by().map_or("PROCESS", AggregateBy::column)
+    }
+
+    fn create_process_lines(&self, processes: &[ProcessSwapInfo]) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+
+        let theme = Theme::from(self.current_theme);
+        let first_column = if self.aggregated { "COUNT" } else { "PID" };
+        let attribution = self.attribution_columns();
+        let mut header = vec![
+            format!("{:>12}", self.sort_header(SortColumn::Pid, first_column)).bold(),
+            " | ".into(),
+            format!(
+                "{:30}",
+                self.sort_header(SortColumn::Name, self.name_column())
+            )
+            .bold(),
+            " | ".into(),
+            format!(
+                "{:10}",
+                self.sort_header(SortColumn::Swap, &self.swap_metric.used_column())
+            )
+            .bold(),
+        ];
+        if attribution {
+            header.extend([
+                " | ".into(),
+                format!("{:30}", self.sort_header(SortColumn::Unit, "UNIT")).bold(),
+                " | ".into(),
+                format!(
+                    "{:27}",
+                    self.sort_header(SortColumn::Container, "CONTAINER")
+                )
+                .bold(),
+            ]);
+        }
+        lines.push(Line::from(header));
+
+        for process in processes {
+            let mut process_size: String = format!("{:.2}", process.swap_size);
+            if let SizeUnits::KB = self.swap_size_unit {
+                process_size = format!("{}", process.swap_size)
+            }
+
+            let mut row = vec![
+                format!("{:12}", process.pid).into(),
+                " | ".into(),
+                format!("{:30}", process.name).into(),
+                " | ".into(),
+                format!("{:10}", process_size).into(),
+            ];
+            if attribution {
+                row.extend([
+                    " | ".into(),
+                    format!("{:30}", fit_column(&process.systemd_unit, 30)).into(),
+                    " | ".into(),
+                    format!("{:27}", process.container()).into(),
+                ]);
+            }
+            lines.push(self.alert_line(Line::from(row), process.swap_size, &theme));
+        }
+
+        lines
+    }
+
+    /// Colors a list row whose swap breaches the per-process threshold.
+    fn alert_line(&self, line: Line<'static>, swap_size: f64, theme: &Theme) -> Line<'static> {
+        match self.alerts.process(self.row_kb(swap_size)).color(theme) {
+            Some(color) => line.fg(color),
+            None => line,
+        }
+    }
+
+    /// Tree view lines, sizes are the swap of the process and of its whole subtree.
+    #[cfg(target_os = "linux")]
+    fn create_tree_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![Line::from(vec![
+            format!("{:>12}", self.sort_header(SortColumn::Pid, "PID")).bold(),
+            " | ".into(),
+            format!("{:30}", self.sort_header(SortColumn::Name, "PROCESS")).bold(),
+            " | ".into(),
+            format!("{:15}", self.swap_metric.used_column()).bold(),
+            " | ".into(),
+            format!("{:12}", self.sort_header(SortColumn::Swap, "SUBTREE")).bold(),
+        ])];
+
+        let theme = Theme::from(self.current_theme);
+        let format_size = |size: f64| match self.swap_size_unit {
+            SizeUnits::KB => format!("{}", size),
+            _ => format!("{:.2}", size),
+        };
+        for row in &self.tree_rows {
+            let marker = match (row.has_children, row.collapsed) {
+                (false, _) => "  ",
+                (true, false) => "▾ ",
+                (true, true) => "▸ ",
+            };
+            let name = format!("{}{}{}", "  ".repeat(row.depth), marker, row.process.name);
+            let line = Line::from(vec![
+                format!("{:12}", row.process.pid).into(),
+                " | ".into(),
+                format!("{:30}", fit_column(&name, 30)).into(),
+                " | ".into(),
+                format!("{:15}", format_size(row.process.swap_size)).into(),
+                " | ".into(),
+                format!("{:12}", format_size(row.total)).into(),
+            ]);
+            lines.push(self.alert_line(line, row.process.swap_size, &theme));
+        }
+
+        lines
+    }
+
+    fn swap_usage_percent(&self) -> f64 {
+        if self.chart_info.total_swap == 0 {
+            return 0.0;
+        }
+        self.chart_info.used_swap as f64 / self.chart_info.total_swap as f64 * 100.0
+    }
+
+    /// Swap of a list row in KB, whatever unit the list is shown in.
+    fn row_kb(&self, swap_size: f64) -> f64 {
+        swap_size / convert_swap(1, self.swap_size_unit.clone())
+    }
+
+    /// Swap-out rate of the last refresh in KB per second.
+    #[cfg(target_os = "linux")]
+    fn swap_out_rate(&self) -> f64 {
+        self.swap_out_data.last().map_or(0.0, |&(_, rate)| rate)
+    }
+
+    /// Every breached threshold with a short description for the banner.
+    fn breaches(&self) -> Vec<(Severity, String)> {
+        let percent = self.swap_usage_percent();
+        let mut breaches = vec![(
+            self.alerts.swap(percent),
+            format!("swap {}%", percent.round() as u64),
+        )];
+
+        #[cfg(target_os = "linux")]
+        {
+            for device in &self.chart_info.swap_devices {
+                let percent = device_percent(device);
+                breaches.push((
+                    self.alerts.device(percent),
+                    format!("{} {}%", device.name, percent.round() as u64),
+                ));
+            }
+            let rate = self.swap_out_rate();
+            breaches.push((
+                self.alerts.swap_out(rate),
+                format!("swap-out {:.1} MB/s", rate / 1024.0),
+            ));
+        }
+
+        let heavy: Vec<(Severity, &ProcessSwapInfo)> = self
+            .process_rows
+            .iter()
+            .map(|process| (self.alerts.process(self.row_kb(process.swap_size)), process))
+            .filter(|(severity, _)| *severity != Severity::Normal)
+            .collect();
+        if let Some((_, largest)) = heavy
+            .iter()
+            .max_by(|a, b| a.1.swap_size.total_cmp(&b.1.swap_size))
+        {
+            // aggregated rows are groups, their totals are not a single process
+            let kind = if self.aggregated { "group " } else { "" };
+            let mut text = format!(
+                "{kind}{} {:.0} MB",
+                largest.name,
+                self.row_kb(largest.swap_size) / 1024.0
+            );
+            if heavy.len() > 1 {
+                let more = if self.aggregated { "groups" } else { "more" };
+                text.push_str(&format!(" (+{} {more})", heavy.len() - 1));
+            }
+            let severity = heavy.iter().map(|(severity, _)| *severity).max();
+            breaches.push((severity.unwrap_or(Severity::Normal), text));
+        }
+
+        breaches.retain(|(severity, _)| *severity != Severity::Normal);
+        breaches
+    }
+
+    /// Draws a flashing banner over the first line of `area` while a threshold
+    /// is breached and returns the space left below it.
+    fn render_alert_banner(&self, frame: &mut Frame, area: Rect, theme: &Theme) -> Rect {
+        let breaches = self.breaches();
+        let Some(severity) = breaches.iter().map(|(severity, _)| *severity).max() else {
+            return area;
+        };
+        let Some(color) = severity.color(theme) else {
+            return area;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+
+        let text = breaches
+            .iter()
+            .map(|(_, text)| text.as_str())
+            .collect::<Vec<_>>()
+            .join(" | ");
+        let elapsed = self.alert_epoch.elapsed();
+        let style = if (elapsed.as_millis() / ALERT_FLASH.as_millis()).is_multiple_of(2) {
+            Style::default().fg(theme.background).bg(color)
+        } else {
+            Style::default().fg(color).bg(theme.background)
+        };
+        let banner = Line::from(format!(" ⚠ {}: {} ", severity.label(), text))
+            .bold()
+            .centered();
+        frame.render_widget(Paragraph::new(banner).style(style), chunks[0]);
+        chunks[1]
+    }
+
+    fn generete_total_used_title(&mut self) -> String {
+        let total = convert_swap(self.chart_info.total_swap, self.swap_size_unit.clone());
+        let used = convert_swap(self.chart_info.used_swap, self.swap_size_unit.clone());
+
+        let total_used_title: String = match self.swap_size_unit {
+            SizeUnits::KB => format!("total: {} | used: {}", total, used),
+            SizeUnits::MB => format!("total: {} | used: {:.2}", total.round(), used),
+            SizeUnits::GB => format!("total: {:.2} | used: {:.2}", total, used),
+        };
+
+        total_used_title
+    }
+
+    #[cfg(target_os = "linux")]
+    fn render_swap_devices(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let total_used_title = self.generete_total_used_title();
+
+        let total_n_used_line = if !self.display_devices {
+            Line::from("").fg(theme.text).left_aligned()
+        } else {
+            Line::from(total_used_title).fg(theme.text).left_aligned()
+        };
+
+        let name_width = self
+            .chart_info
+            .swap_devices
+            .iter()
+            .map(|d| d.name.len())
+            .max()
+            .unwrap_or(10)
+            .max(10);
+
+        let source_width = self
+            .chart_info
+            .swap_devices
+            .iter()
+            .map(|d| {
+                let src = find_mount_device(std::path::Path::new(&d.name))
+                    .unwrap_or_else(|| "RAM".into());
+                src.len()
+            })
+            .max()
+            .unwrap_or(4)
+            .max(4);
+
+        let wide = area.width >= 80;
+        let mut lines = Vec::new();
+
+        if wide {
+            lines.push(Line::from(format!(
+                "{:<source_width$} | {:<name_width$} | {:<10} | {:>8} | {:>10} | {:>10}",
+                "disk", "path", "type", "priority", "total", "used"
+            )));
+        } else {
+            lines.push(Line::from(format!(
+                "{:<source_width$} | {:<name_width$} | {:<10} | {:>10}",
+                "disk", "path", "total", "used"
+            )));
+        }
+
+        for device in &self.chart_info.swap_devices {
+            let used = match self.swap_size_unit {
+                SizeUnits::KB => device.used_kb.to_string(),
+                _ => format!("{:.2}", device.used_kb),
+            };
+
+            let source = find_mount_device(std::path::Path::new(&device.name))
+                .unwrap_or_else(|| "RAM".into());
+
+            let total = match self.swap_size_unit {
+                SizeUnits::KB => device.size_kb.to_string(),
+                _ => format!("{:.2}", device.size_kb),
+            };
+
+            let row = if wide {
+                format!(
+                    "{:<source_width$} | {:<name_width$} | {:<10} | {:>8} | {:>10} | {:>10}",
+                    source, device.name, device.kind, device.priority, total, used
+                )
+            } else {
+                format!(
+                    "{:<source_width$} | {:<name_width$} | {:<10} | {:>10}",
+                    source, device.name, total, used
+                )
+            };
+            let row = Line::from(row);
+            lines.push(
+                match self.alerts.device(device_percent(device)).color(theme) {
+                    Some(color) => row.fg(color),
+                    None => row,
+                },
+            );
+        }
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.background))
+            .title(total_n_used_line.right_aligned())
+            .title(Line::from("swap devices").fg(theme.text).left_aligned())
+            .title_bottom(Line::from("(h to hide swap devices)").left_aligned());
+
+        let para = Paragraph::new(lines).block(block).centered();
+        frame.render_widget(para, area);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn render_zram_devices(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let size = |bytes: u64| {
+            let value = convert_swap(bytes / 1024, self.swap_size_unit.clone());
+            match self.swap_size_unit {
+                SizeUnits::KB => value.to_string(),
+                _ => format!("{:.2}", value),
+            }
+        };
+
+        let mut lines = vec![Line::from(format!(
+            "{:<11} | {:<8} | {:>10} | {:>10} | {:>10} | {:>10} | {:>6} | {:>8} | {:>8}",
+            "device", "algo", "disk", "data", "compr", "mem used", "ratio", "same", "huge"
+        ))];
+        for device in &self.chart_info.zram_devices {
+            lines.push(Line::from(format!(
+                "{:<11} | {:<8} | {:>10} | {:>10} | {:>10} | {:>10} | {:>6.2} | {:>8} | {:>8}",
+                device.name,
+                device.algorithm,
+                size(device.disk_size),
+                size(device.orig_data_size),
+                size(device.compr_data_size),
+                size(device.mem_used_total),
+                device.compression_ratio(),
+                device.same_pages,
+                device.huge_pages
+            )));
+        }
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.background))
+            .title(Line::from("zram").fg(theme.text).left_aligned());
+
+        let para = Paragraph::new(lines).block(block).centered();
+        frame.render_widget(para, area);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn render_cgroup_tree(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let Some(tree) = &self.cgroup_tree else {
+            return;
+        };
+        self.visible_height = area.height as usize;
+
+        let limit = |bytes: Option<u64>| match bytes {
+            Some(bytes) => self.format_kb(bytes / 1024),
+            None => "max".to_owned(),
+        };
+        let name_width = tree
+            .iter()
+            .map(|c| c.depth * 2 + c.path.rsplit('/').next().unwrap_or_default().len())
+            .max()
+            .unwrap_or(0)
+            .clamp(6, 60);
+
+        let mut lines = vec![Line::from(
+            format!(
+                "{:<name_width$} | {:>14} | {:>14} | {:>14} | {:>6} | {:>8} | {:>8} | {:>8}",
+                "CGROUP", "SWAP", "MAX", "HIGH", "USED", "EV HIGH", "EV MAX", "EV FAIL"
+            )
+            .bold(),
+        )];
+        for cgroup in tree.iter().skip(self.cgroup_scroll) {
+            let name = format!(
+                "{}{}",
+                "  ".repeat(cgroup.depth),
+                cgroup.path.rsplit('/').next().unwrap_or_default()
+            );
+            let used = cgroup
+                .usage_ratio()
+                .map(|ratio| format!("{:.0}%", ratio * 100.0))
+                .unwrap_or_else(|| "-".into());
+            let color = cgroup
+                .usage_ratio()
+                .and_then(|ratio| self.alerts.cgroup(ratio * 100.0).color(theme))
+                .unwrap_or(theme.text);
+            lines.push(
+                Line::from(format!(
+                    "{:<name_width$} | {:>14} | {:>14} | {:>14} | {:>6} | {:>8} | {:>8} | {:>8}",
+                    name,
+                    self.format_kb(cgroup.current / 1024),
+                    limit(cgroup.max),
+                    limit(cgroup.high),
+                    used,
+                    cgroup.events.high,
+                    cgroup.events.max,
+                    cgroup.events.fail
+                ))
+                .fg(color),
+            );
+        }
+        if tree.is_empty() {
+            lines.push(Line::from("no cgroup uses swap or has a swap limit"));
+        }
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.background))
+            .title(
+                Line::from("swap by cgroup")
+                    .fg(theme.primary)
+                    .bold()
+                    .left_aligned(),
+            )
+            .title(
+                Line::from("(u/d|▲/▼|home/end|pgup/pgdown to scroll)")
+                    .fg(theme.text)
+                    .right_aligned(),
+            )
+            .title_bottom(Line::from("(v/esc to close)").right_aligned());
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn format_kb(&self, kb: u64) -> String {
+        let value = convert_swap(kb, self.swap_size_unit.clone());
+        match self.swap_size_unit {
+            SizeUnits::KB => format!("{} {:?}", value, self.swap_size_unit),
+            _ => format!("{:.2} {:?}", value, self.swap_size_unit),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn render_swap_breakdown(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let Some(breakdown) = &self.breakdown else {
+            return;
+        };
+        self.visible_height = area.height as usize;
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.background))
+            .title(
+                Line::from(format!(
+                    "swap of {} ({}) by mapping",
+                    breakdown.name, breakdown.pid
+                ))
+                .fg(theme.primary)
+                .bold()
+                .left_aligned(),
+            )
+            .title(
+                Line::from("(u/d|▲/▼|home/end|pgup/pgdown to scroll)")
+                    .fg(theme.text)
+                    .right_aligned(),
+            )
+            .title_bottom(Line::from("(b/esc to close)").right_aligned());
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(breakdown.totals.len().max(1) as u16 + 1),
+                Constraint::Min(3),
+            ])
+            .split(inner);
+
+        let mut totals = vec![Line::from(vec![
+            format!("{:<8}", "CATEGORY").bold(),
+            format!("{:>16}", "SWAP").bold(),
+            format!("{:>16}", "SWAPPSS").bold(),
+            format!("{:>10}", "MAPPINGS").bold(),
+        ])];
+        for total in &breakdown.totals {
+            totals.push(Line::from(vec![
+                format!("{:<8}", total.category.label()).fg(theme.secondary),
+                format!("{:>16}", self.format_kb(total.swap_kb)).into(),
+                format!("{:>16}", self.format_kb(total.swap_pss_kb)).into(),
+                format!("{:>10}", total.mappings).into(),
+            ]));
+        }
+        if breakdown.totals.is_empty() {
+            totals.push(Line::from("no mapping of this process is in swap"));
+        }
+        frame.render_widget(
+            Paragraph::new(totals).style(Style::default().fg(theme.text)),
+            chunks[0],
+        );
+
+        let mut lines = vec![Line::from(vec![
+            format!(
+                "{:<33} | {:5} | {:6} | {:>14} | {:>14} | ",
+                "ADDRESS", "PERMS", "KIND", "SWAP", "SWAPPSS"
+            )
+            .bold(),
+            "MAPPING".bold(),
+        ])];
+        for mapping in breakdown.mappings.iter().skip(self.breakdown_scroll) {
+            lines.push(Line::from(vec![
+                format!(
+                    "{:016x}-{:016x} | {:5} | {:6} | {:>14} | {:>14} | ",
+                    mapping.start,
+                    mapping.end,
+                    mapping.perms,
+                    mapping.category.label(),
+                    self.format_kb(mapping.swap_kb),
+                    self.format_kb(mapping.swap_pss_kb)
+                )
+                .into(),
+                mapping.path.clone().fg(theme.secondary),
+            ]));
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::TOP)
+                    .border_style(Style::default().fg(theme.border)),
+            ),
+            chunks[1],
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    fn render_device_manager(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let Some(manager) = &self.device_manager else {
+            return;
+        };
+
+        let size = |kb: u64| self.format_kb(kb);
+        let label_width = manager
+            .entries
+            .iter()
+            .map(|e| e.label.len())
+            .max()
+            .unwrap_or(4)
+            .max(4);
+        let mut lines = vec![
+            Line::from(format!(
+                " {:<label_width$} | {:<9} | {:>12} | {:>12} | {:>8} | {}",
+                "path", "type", "total", "used", "priority", "state"
+            ))
+            .fg(theme.text)
+            .bold(),
+        ];
+        for (i, entry) in manager.entries.iter().enumerate() {
+            let (total, used) = if entry.active {
+                (size(entry.size_kb), size(entry.used_kb))
+            } else {
+                (String::new(), String::new())
+            };
+            let priority = entry.priority.map(|p| p.to_string()).unwrap_or_default();
+            let line = Line::from(format!(
+                " {:<label_width$} | {:<9} | {:>12} | {:>12} | {:>8} | {}",
+                entry.label,
+                entry.kind,
+                total,
+                used,
+                priority,
+                if entry.active { "on" } else { "off" }
+            ));
+            lines.push(if i == manager.selected {
+                line.fg(theme.background).bg(theme.primary).bold()
+            } else {
+                line.fg(theme.text)
+            });
+        }
+        if manager.entries.is_empty() {
+            lines.push(Line::from(
+                " no active swap and no swap entries in /etc/fstab",
+            ));
+        }
+        lines.push(Line::from(""));
+
+        let selected = manager.selected_entry();
+        if let Some((description, _)) = &manager.running {
+            lines.push(
+                Line::from(format!(" {description} ..."))
+                    .fg(theme.secondary)
+                    .bold(),
+            );
+        } else if let Some((path, action)) = &manager.confirm {
+            lines.push(
+                Line::from(format!(" {}? (y/n)", action.describe(path)))
+                    .fg(theme.secondary)
+                    .bold(),
+            );
+            if let Some(entry) = selected
+                && manager.exceeds_available()
+            {
+                lines.push(
+                    Line::from(format!(
+                        " warning: {} used on it exceeds the {} of available RAM",
+                        size(entry.used_kb),
+                        size(manager.mem_available_kb)
+                    ))
+                    .fg(theme.secondary),
+                );
+            }
+        } else if let Some(form) = &manager.new_file {
+            lines.push(Line::from(" new swap file").fg(theme.secondary).bold());
+            for (i, (label, value)) in NewSwapFile::LABELS.iter().zip(form.values()).enumerate() {
+                let cursor = if i == form.field { "_" } else { "" };
+                let line = Line::from(format!("   {label:<9}{value}{cursor}"));
+                lines.push(if i == form.field {
+                    line.fg(theme.primary).bold()
+                } else {
+                    line.fg(theme.text)
+                });
+            }
+        } else if let Some(input) = &manager.priority_input {
+            lines.push(
+                Line::from(format!(" priority (0-{MAX_PRIORITY}): {input}_"))
+                    .fg(theme.secondary)
+                    .bold(),
+            );
+        }
+        if let Some(error) = &manager.error {
+            lines.push(Line::from(format!(" {error}")).fg(theme.secondary).bold());
+        }
+
+        let width = area.width.min((label_width + 70) as u16);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.primary))
+            .style(Style::default().bg(theme.background))
+            .title(
+                Line::from(" swap devices ")
+                    .fg(theme.primary)
+                    .bold()
+                    .left_aligned(),
+            )
+            .title_bottom(
+                Line::from("(enter for on/off, p for priority, n for new file, esc to close)")
+                    .right_aligned(),
+            );
+        frame.render_widget(Clear, popup);
+        frame.render_widget(Paragraph::new(lines).block(block), popup);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn render_swap_in(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let Some(swap_in) = &self.swap_in else {
+            return;
+        };
+
+        let size = |bytes: u64| self.format_kb(bytes / 1024);
+        let field = |label: &str, value: String| {
+            Line::from(vec![
+                format!(" {:<14}", label).fg(theme.secondary).bold(),
+                value.fg(theme.text),
+            ])
+        };
+        let mut lines = vec![
+            field("swap (smaps)", size(swap_in.estimate)),
+            field(
+                "swapped pages",
+                format!("{} ({})", swap_in.total_pages, size(swap_in.total_bytes())),
+            ),
+            field("MemAvailable", size(swap_in.mem_available)),
+            Line::from(""),
+        ];
+        let hint = if swap_in.started {
+            "(esc to cancel)"
+        } else if swap_in.fits() {
+            lines.push(Line::from(" fits in available memory").fg(theme.text));
+            "(enter to swap in, esc to close)"
+        } else {
+            lines.push(
+                Line::from(" not enough available memory to swap it in")
+                    .fg(theme.secondary)
+                    .bold(),
+            );
+            "(esc to close)"
+        };
+
+        let width = area.width.min(56);
+        let height = (lines.len() as u16 + 3).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.primary))
+            .style(Style::default().bg(theme.background))
+            .title(
+                Line::from(format!(" swap in {} ({}) ", swap_in.name, swap_in.pid))
+                    .fg(theme.primary)
+                    .bold()
+                    .left_aligned(),
+            )
+            .title_bottom(Line::from(hint).right_aligned());
+        let inner = block.inner(popup);
+        frame.render_widget(Clear, popup);
+        frame.render_widget(block, popup);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(inner);
+        frame.render_widget(Paragraph::new(lines), chunks[0]);
+        frame.render_widget(
+            Gauge::default()
+                .gauge_style(Style::default().fg(theme.primary).bg(theme.background))
+                .ratio(if swap_in.started {
+                    swap_in.progress()
+                } else {
+                    0.0
+                })
+                .label(format!(
+                    "{} / {}",
+                    size(swap_in.done_bytes()),
+                    size(swap_in.total_bytes())
+                )),
+            chunks[1],
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    fn render_signal_dialog(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let Some(dialog) = &self.signal_dialog else {
+            return;
+        };
+
+        let mut lines: Vec<Line> = Signal::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, signal)| {
+                let line = Line::from(format!(" {:8} {}", signal.name(), signal.description()));
+                if i == dialog.selected {
+                    line.fg(theme.background).bg(theme.primary).bold()
+                } else {
+                    line.fg(theme.text)
+                }
+            })
+            .collect();
+        lines.push(Line::from(""));
+        if dialog.confirming {
+            lines.push(
+                Line::from(format!(
+                    " send {} to {}? (y/n)",
+                    dialog.signal().name(),
+                    dialog.title
+                ))
+                .fg(theme.secondary)
+                .bold(),
+            );
+            for (pid, name) in dialog.targets.iter().take(3) {
+                lines.push(Line::from(format!("   {pid} {name}")).fg(theme.text));
+            }
+            if dialog.targets.len() > 3 {
+                lines.push(Line::from(format!(
+                    "   and {} more",
+                    dialog.targets.len() - 3
+                )));
+            }
+        } else if let Some(error) = &dialog.error {
+            lines.push(Line::from(format!(" {error}")).fg(theme.secondary).bold());
+        }
+
+        let width = area.width.min(64);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.primary))
+            .style(Style::default().bg(theme.background))
+            .title(
+                Line::from(format!(" signal {} ", dialog.title))
+                    .fg(theme.primary)
+                    .bold()
+                    .left_aligned(),
+            )
+            .title_bottom(Line::from("(enter to pick, esc to close)").right_aligned());
+        frame.render_widget(Clear, popup);
+        frame.render_widget(Paragraph::new(lines).block(block), popup);
+    }
+
+    #[cfg(target_os = "linux")]
+    fn render_process_detail(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let Some(detail) = &self.detail else {
+            return;
+        };
+
+        let size = |kb: u64| self.format_kb(kb);
+        let field = |label: &str, value: String| {
+            Line::from(vec![
+                format!("{:<9}", label).fg(theme.secondary).bold(),
+                value.into(),
+            ])
+        };
+
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.background))
+            .title(
+                Line::from(format!("{} ({})", detail.name, detail.pid))
+                    .fg(theme.primary)
+                    .bold()
+                    .left_aligned(),
+            )
+            .title_bottom(Line::from("(enter/esc to close)").right_aligned());
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(8), Constraint::Length(5)])
+            .split(inner);
+
+        let lines = vec![
+            field("command", detail.cmdline.clone()),
+            field("user", detail.user.clone()),
+            field("state", detail.state.clone()),
+            field("rss", size(detail.rss_kb)),
+            field("swap", size(detail.swap_kb)),
+            field("cgroup", detail.cgroup.clone()),
+            field("started", detail.start_time.clone()),
+        ];
+        frame.render_widget(
+            Paragraph::new(lines)
+                .style(Style::default().fg(theme.text))
+                .wrap(Wrap { trim: false }),
+            chunks[0],
+        );
+
+        let sparkline = Sparkline::default()
+            .block(
+                Block::default()
+                    .borders(Borders::TOP)
+                    .border_style(Style::default().fg(theme.border))
+                    .title(Line::from("swap history").fg(theme.text)),
+            )
+            .data(&detail.swap_history)
+            .style(Style::default().fg(theme.primary));
+        frame.render_widget(sparkline, chunks[1]);
+    }
+
+    fn render_animated_chart(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        #[cfg(target_os = "linux")]
+        match self.chart_mode {
+            ChartMode::Usage => {}
+            ChartMode::SwapIo => return self.render_io_chart(frame, area, theme),
+            ChartMode::Pressure => return self.render_pressure_chart(frame, area, theme),
+        }
+
+        let total_used_title = self.generete_total_used_title();
+
+        let total_n_used_line = if self.display_devices {
+            Line::from("").fg(theme.text).left_aligned()
+        } else {
+            Line::from(total_used_title).fg(theme.text).left_aligned()
+        };
+
+        let swap_usage_percent = self.swap_usage_percent();
+        let usage_color = self
+            .alerts
+            .swap(swap_usage_percent)
+            .color(theme)
+            .unwrap_or(theme.primary);
+        let datasets = vec![
+            Dataset::default()
+                .marker(Marker::Braille)
+                .style(Style::default().fg(theme.primary))
+                .graph_type(GraphType::Line)
+                .data(&self.chart_data),
+        ];
+
+        let bottom_title = if LINUX && !self.display_devices {
+            "(h to show swap devices)"
+        } else {
+            ""
+        };
+        let chart = Chart::new(datasets)
+            .block(
+                Block::bordered()
                     .border_type(BorderType::Rounded)
                     .border_style(Style::default().fg(theme.border))
                     .title(
                         Line::from(format!("swap usage {}%", swap_usage_percent.round() as u64))
-                            .fg(theme.primary)
+                            .fg(usage_color)
                             .bold()
                             .right_aligned(),
                     )
                     .title(total_n_used_line)
+                    .title(
+                        Line::from(self.zswap_title())
+                            .fg(theme.secondary)
+                            .centered(),
+                    )
                     .title_bottom(Line::from(bottom_title).left_aligned())
+                    .title_bottom(
+                        Line::from(if LINUX { "(c to change chart)" } else { "" }).right_aligned(),
+                    )
                     .border_style(Style::default().fg(theme.border))
                     .style(Style::default().bg(theme.background)),
             )
@@ -638,6 +2852,186 @@ impl App {
         frame.render_widget(chart, area);
     }
 
+    #[cfg(target_os = "linux")]
+    fn render_pressure_gauge(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let Some(pressure) = &self.chart_info.pressure else {
+            return;
+        };
+
+        let record = |name: &str, r: &crate::pressure::PressureRecord| {
+            format!("{} {:.2}% {:.2}% {:.2}%", name, r.avg10, r.avg60, r.avg300)
+        };
+        let stall = self
+            .stall_rate
+            .map(|rate| format!(" | stalled {:.0} ms/s", rate))
+            .unwrap_or_default();
+        let label = format!(
+            "memory pressure (avg10 avg60 avg300) {} | {}{}",
+            record("some", &pressure.some),
+            record("full", &pressure.full),
+            stall
+        );
+
+        let gauge = LineGauge::default()
+            .filled_style(Style::default().fg(theme.primary))
+            .unfilled_style(Style::default().fg(theme.border))
+            .label(Line::from(label).fg(theme.text))
+            .ratio((pressure.some.avg10 as f64 / 100.0).clamp(0.0, 1.0));
+        frame.render_widget(gauge, area);
+    }
+
+    /// Plots the `some` and `full` avg10 memory pressure in percent.
+    #[cfg(target_os = "linux")]
+    fn render_pressure_chart(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let datasets = vec![
+            Dataset::default()
+                .name("some")
+                .marker(Marker::Braille)
+                .style(Style::default().fg(theme.primary))
+                .graph_type(GraphType::Line)
+                .data(&self.pressure_some_data),
+            Dataset::default()
+                .name("full")
+                .marker(Marker::Braille)
+                .style(Style::default().fg(theme.secondary))
+                .graph_type(GraphType::Line)
+                .data(&self.pressure_full_data),
+        ];
+
+        let title = match &self.chart_info.pressure {
+            Some(pressure) => Line::from(vec![
+                format!("some {:.2}%", pressure.some.avg10).fg(theme.primary),
+                " | ".fg(theme.text),
+                format!("full {:.2}%", pressure.full.avg10).fg(theme.secondary),
+            ]),
+            None => Line::from("PSI is not available on this kernel").fg(theme.text),
+        };
+        let max_pressure = self
+            .pressure_some_data
+            .iter()
+            .map(|&(_, value)| value)
+            .fold(0.0, f64::max);
+
+        let bottom_title = if self.display_devices {
+            ""
+        } else {
+            "(h to show swap devices)"
+        };
+        let chart = Chart::new(datasets)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(theme.border))
+                    .title(
+                        Line::from("memory pressure avg10")
+                            .fg(theme.primary)
+                            .bold()
+                            .right_aligned(),
+                    )
+                    .title(title.left_aligned())
+                    .title_bottom(Line::from(bottom_title).left_aligned())
+                    .title_bottom(Line::from("(c to change chart)").right_aligned())
+                    .style(Style::default().bg(theme.background)),
+            )
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(theme.text))
+                    .bounds(self.time_window),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(theme.text))
+                    .bounds([0.0, (max_pressure * 1.1).clamp(1.0, 100.0)]),
+            );
+
+        frame.render_widget(chart, area);
+    }
+
+    /// Plots the swap-in and swap-out rates in the current unit per second.
+    #[cfg(target_os = "linux")]
+    fn render_io_chart(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let scale = |data: &[(f64, f64)]| -> Vec<(f64, f64)> {
+            data.iter()
+                .map(|&(x, kb)| (x, convert_swap(1, self.swap_size_unit.clone()) * kb))
+                .collect()
+        };
+        let swap_in = scale(&self.swap_in_data);
+        let swap_out = scale(&self.swap_out_data);
+        let rate = |data: &[(f64, f64)]| {
+            let value = data.last().map(|&(_, rate)| rate).unwrap_or(0.0);
+            match self.swap_size_unit {
+                SizeUnits::KB => format!("{:.0} {:?}/s", value, self.swap_size_unit),
+                _ => format!("{:.2} {:?}/s", value, self.swap_size_unit),
+            }
+        };
+        let out_color = self
+            .alerts
+            .swap_out(self.swap_out_rate())
+            .color(theme)
+            .unwrap_or(theme.secondary);
+        let max_rate = swap_in
+            .iter()
+            .chain(&swap_out)
+            .map(|&(_, rate)| rate)
+            .fold(0.0, f64::max);
+
+        let datasets = vec![
+            Dataset::default()
+                .name("in")
+                .marker(Marker::Braille)
+                .style(Style::default().fg(theme.primary))
+                .graph_type(GraphType::Line)
+                .data(&swap_in),
+            Dataset::default()
+                .name("out")
+                .marker(Marker::Braille)
+                .style(Style::default().fg(theme.secondary))
+                .graph_type(GraphType::Line)
+                .data(&swap_out),
+        ];
+
+        let bottom_title = if self.display_devices {
+            ""
+        } else {
+            "(h to show swap devices)"
+        };
+        let chart = Chart::new(datasets)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(theme.border))
+                    .title(
+                        Line::from("swap I/O")
+                            .fg(theme.primary)
+                            .bold()
+                            .right_aligned(),
+                    )
+                    .title(
+                        Line::from(vec![
+                            format!("in {}", rate(&swap_in)).fg(theme.primary),
+                            " | ".fg(theme.text),
+                            format!("out {}", rate(&swap_out)).fg(out_color),
+                        ])
+                        .left_aligned(),
+                    )
+                    .title_bottom(Line::from(bottom_title).left_aligned())
+                    .title_bottom(Line::from("(c to change chart)").right_aligned())
+                    .style(Style::default().bg(theme.background)),
+            )
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(theme.text))
+                    .bounds(self.time_window),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(theme.text))
+                    .bounds([0.0, max_rate.max(1.0) * 1.1]),
+            );
+
+        frame.render_widget(chart, area);
+    }
+
     fn render_processes_list(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let unit_buttons = match self.swap_size_unit {
             SizeUnits::KB => "▶KB◀─MB─GB",
@@ -650,6 +3044,18 @@ impl App {
         self.vertical_scroll = self
             .vertical_scroll
             .min(content_height.saturating_sub(self.visible_height));
+
+        // keep the selected row (below the header line) inside the viewport
+        let inner_height = self.visible_height.saturating_sub(2).max(1);
+        let selected_line = self.selection + 1;
+        if self.selection == 0 {
+            self.vertical_scroll = 0;
+        } else if selected_line < self.vertical_scroll {
+            self.vertical_scroll = selected_line;
+        } else if selected_line >= self.vertical_scroll + inner_height {
+            self.vertical_scroll = selected_line + 1 - inner_height;
+        }
+
         self.vertical_scroll_state = self
             .vertical_scroll_state
             .content_length(content_height)
@@ -659,19 +3065,34 @@ impl App {
             .border_type(BorderType::Rounded)
             .border_style(Style::default().fg(theme.border))
             .style(Style::default().bg(theme.background))
-            .title(
-                Line::from("(a to aggregate) (u/d|▲/▼|home/end|pgup/pgdown to scroll)")
-                    .fg(theme.text)
-                    .right_aligned(),
-            )
+            .title(Line::from(self.key_hints()).fg(theme.text).right_aligned())
             .title(
                 Line::from(format!("unit (k/m/g to change): {}", unit_buttons))
                     .fg(theme.secondary)
                     .bold()
                     .left_aligned(),
+            )
+            .title_bottom(
+                Line::from(self.filter_title())
+                    .fg(theme.secondary)
+                    .left_aligned(),
+            )
+            .title_bottom(
+                Line::from(self.metric_title())
+                    .fg(theme.secondary)
+                    .right_aligned(),
             );
 
-        let process_paragraph = Paragraph::new(self.swap_processes_lines.clone())
+        let mut lines = self.swap_processes_lines.clone();
+        if !self.process_rows.is_empty()
+            && let Some(line) = lines.get_mut(selected_line)
+        {
+            *line = line
+                .clone()
+                .style(Style::default().fg(theme.background).bg(theme.primary));
+        }
+
+        let process_paragraph = Paragraph::new(lines)
             .alignment(Alignment::Center)
             .block(bottom_block)
             .scroll((self.vertical_scroll as u16, 0));
@@ -0,0 +1,175 @@
+use procfs::process::{MMapPath, Process};
+
+use crate::swap_info::SwapDataError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MappingCategory {
+    Heap,
+    Stack,
+    Anonymous,
+    SharedMemory,
+    File,
+    Other,
+}
+
+impl MappingCategory {
+    pub fn label(self) -> &'static str {
+        match self {
+            MappingCategory::Heap => "heap",
+            MappingCategory::Stack => "stack",
+            MappingCategory::Anonymous => "anon",
+            MappingCategory::SharedMemory => "shmem",
+            MappingCategory::File => "file",
+            MappingCategory::Other => "other",
+        }
+    }
+
+    fn of(path: &MMapPath) -> Self {
+        match path {
+            MMapPath::Heap => MappingCategory::Heap,
+            MMapPath::Stack | MMapPath::TStack(_) => MappingCategory::Stack,
+            MMapPath::Anonymous => MappingCategory::Anonymous,
+            MMapPath::Vsys(_) => MappingCategory::SharedMemory,
+            // memfd mappings read `/memfd:NAME (deleted)`, a single path component
+            MMapPath::Path(path)
+                if path.starts_with("/dev/shm")
+                    || path.to_string_lossy().starts_with("/memfd:") =>
+            {
+                MappingCategory::SharedMemory
+            }
+            MMapPath::Path(_) => MappingCategory::File,
+            _ => MappingCategory::Other,
+        }
+    }
+}
+
+/// A single entry of `/proc/PID/smaps` that has pages in swap.
+#[derive(Debug, Clone)]
+pub struct SwapMapping {
+    pub start: u64,
+    pub end: u64,
+    pub perms: String,
+    pub path: String,
+    pub category: MappingCategory,
+    pub swap_kb: u64,
+    pub swap_pss_kb: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CategoryTotal {
+    pub category: MappingCategory,
+    pub mappings: usize,
+    pub swap_kb: u64,
+    pub swap_pss_kb: u64,
+}
+
+/// Swapped memory of one process broken down by mapping, largest first.
+#[derive(Debug, Clone)]
+pub struct SwapBreakdown {
+    pub pid: u32,
+    pub name: String,
+    pub mappings: Vec<SwapMapping>,
+    pub totals: Vec<CategoryTotal>,
+}
+
+fn mapping_path(path: &MMapPath) -> String {
+    match path {
+        MMapPath::Path(path) => path.to_string_lossy().into_owned(),
+        MMapPath::Heap => "[heap]".into(),
+        MMapPath::Stack => "[stack]".into(),
+        MMapPath::TStack(tid) => format!("[stack:{tid}]"),
+        MMapPath::Vdso => "[vdso]".into(),
+        MMapPath::Vvar => "[vvar]".into(),
+        MMapPath::Vsyscall => "[vsyscall]".into(),
+        MMapPath::Rollup => "[rollup]".into(),
+        MMapPath::Anonymous => "[anon]".into(),
+        MMapPath::Vsys(key) => format!("[sysv shm {key}]"),
+        MMapPath::Other(other) => other.clone(),
+    }
+}
+
+impl SwapBreakdown {
+    /// Parses `/proc/PID/smaps`. Like `smaps_rollup`, reading it for another
+    /// user's process requires root.
+    pub fn read(pid: u32) -> Result<Self, SwapDataError> {
+        let process = Process::new(pid as i32)?;
+        let name = process.stat()?.comm;
+
+        // procfs reports the smaps sizes in bytes
+        let mut mappings: Vec<SwapMapping> = process
+            .smaps()?
+            .into_iter()
+            .filter_map(|map| {
+                let swap_kb = map.extension.map.get("Swap").copied().unwrap_or(0) / 1024;
+                if swap_kb == 0 {
+                    return None;
+                }
+                Some(SwapMapping {
+                    start: map.address.0,
+                    end: map.address.1,
+                    perms: map.perms.as_str(),
+                    path: mapping_path(&map.pathname),
+                    category: MappingCategory::of(&map.pathname),
+                    swap_kb,
+                    swap_pss_kb: map.extension.map.get("SwapPss").copied().unwrap_or(0) / 1024,
+                })
+            })
+            .collect();
+        mappings.sort_by_key(|m| std::cmp::Reverse(m.swap_kb));
+
+        let mut totals: Vec<CategoryTotal> = Vec::new();
+        for mapping in &mappings {
+            if let Some(total) = totals.iter_mut().find(|t| t.category == mapping.category) {
+                total.mappings += 1;
+                total.swap_kb += mapping.swap_kb;
+                total.swap_pss_kb += mapping.swap_pss_kb;
+            } else {
+                totals.push(CategoryTotal {
+                    category: mapping.category,
+                    mappings: 1,
+                    swap_kb: mapping.swap_kb,
+                    swap_pss_kb: mapping.swap_pss_kb,
+                });
+            }
+        }
+        totals.sort_by_key(|t| std::cmp::Reverse(t.swap_kb));
+
+        Ok(Self {
+            pid,
+            name,
+            mappings,
+            totals,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn categorizes_mappings() {
+        let path = |p: &str| MMapPath::Path(PathBuf::from(p));
+        let cases = [
+            (MMapPath::Heap, MappingCategory::Heap),
+            (MMapPath::Stack, MappingCategory::Stack),
+            (MMapPath::TStack(42), MappingCategory::Stack),
+            (MMapPath::Anonymous, MappingCategory::Anonymous),
+            (MMapPath::Vsys(7), MappingCategory::SharedMemory),
+            (path("/dev/shm/pulse-shm-1"), MappingCategory::SharedMemory),
+            (
+                path("/memfd:wayland-cursor (deleted)"),
+                MappingCategory::SharedMemory,
+            ),
+            (path("/memfd: (deleted)"), MappingCategory::SharedMemory),
+            (path("/usr/lib/libc.so.6"), MappingCategory::File),
+            (path("/dev/shmem"), MappingCategory::File),
+            (path("/tmp/memfd:notes"), MappingCategory::File),
+            (MMapPath::Vdso, MappingCategory::Other),
+        ];
+        for (path, category) in cases {
+            assert_eq!(MappingCategory::of(&path), category, "{path:?}");
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use procfs::Current;
+use serde::{Deserialize, Serialize};
+
+/// Share of time tasks were stalled on memory, in percent, and the total
+/// stall time since boot in microseconds.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PressureRecord {
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    pub total_us: u64,
+}
+
+/// Memory pressure stall information from `/proc/pressure/memory`. `some` counts
+/// time at least one task was stalled, `full` time all non-idle tasks were.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryPressure {
+    pub some: PressureRecord,
+    pub full: PressureRecord,
+}
+
+impl From<procfs::PressureRecord> for PressureRecord {
+    fn from(record: procfs::PressureRecord) -> Self {
+        Self {
+            avg10: record.avg10,
+            avg60: record.avg60,
+            avg300: record.avg300,
+            total_us: record.total,
+        }
+    }
+}
+
+impl From<procfs::MemoryPressure> for MemoryPressure {
+    fn from(pressure: procfs::MemoryPressure) -> Self {
+        Self {
+            some: pressure.some.into(),
+            full: pressure.full.into(),
+        }
+    }
+}
+
+/// Returns `None` when the kernel has no PSI support or it was disabled with `psi=0`.
+pub fn get_memory_pressure() -> Option<MemoryPressure> {
+    procfs::MemoryPressure::current().ok().map(Into::into)
+}
+
+/// Milliseconds stalled per second between two readings of the `total` counter.
+pub fn stall_rate(previous_us: u64, current_us: u64, seconds: f64) -> f64 {
+    if seconds <= 0.0 {
+        return 0.0;
+    }
+    current_us.saturating_sub(previous_us) as f64 / 1000.0 / seconds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use procfs::FromRead;
+
+    #[test]
+    fn parses_proc_pressure_memory() {
+        let text = "\
+some avg10=1.53 avg60=0.87 avg300=0.22 total=8412005
+full avg10=0.50 avg60=0.25 avg300=0.00 total=3050123
+";
+        let pressure: MemoryPressure = procfs::MemoryPressure::from_read(text.as_bytes())
+            .unwrap()
+            .into();
+        assert_eq!(pressure.some.avg10, 1.53);
+        assert_eq!(pressure.some.avg60, 0.87);
+        assert_eq!(pressure.some.avg300, 0.22);
+        assert_eq!(pressure.some.total_us, 8412005);
+        assert_eq!(pressure.full.avg10, 0.5);
+        assert_eq!(pressure.full.total_us, 3050123);
+    }
+
+    #[test]
+    fn stall_rate_is_milliseconds_per_second() {
+        assert_eq!(stall_rate(1_000_000, 1_500_000, 2.0), 250.0);
+        assert_eq!(stall_rate(0, 0, 1.0), 0.0);
+        // a counter that went backwards or no elapsed time reads as no stall
+        assert_eq!(stall_rate(2_000, 1_000, 1.0), 0.0);
+        assert_eq!(stall_rate(0, 1_000, 0.0), 0.0);
+    }
+}
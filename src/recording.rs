@@ -0,0 +1,404 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+use crate::swap_info::{ProcessSwapInfo, SizeUnits, SwapMetric, SwapUpdate};
+
+const MIN_SPEED: f64 = 0.25;
+const MAX_SPEED: f64 = 16.0;
+// gaps between appended sessions are not replayed in real time
+const MAX_DELAY_MS: u64 = 10000;
+
+/// One refresh of the TUI as stored in a recording, one JSON object per line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedUpdate {
+    pub timestamp_ms: u64,
+    pub unit: SizeUnits,
+    #[serde(default)]
+    pub metric: SwapMetric,
+    pub swap: SwapUpdate,
+    pub processes: Vec<ProcessSwapInfo>,
+}
+
+#[derive(Debug, Error)]
+pub enum RecordingError {
+    #[error("I/O error accessing {0}: {1}")]
+    Io(PathBuf, io::Error),
+    #[error("invalid record on line {0} of {1}: {2}")]
+    Parse(usize, PathBuf, serde_json::Error),
+    #[error("{0} does not contain any record")]
+    Empty(PathBuf),
+}
+
+fn to_kb(value: f64, unit: &SizeUnits) -> f64 {
+    match unit {
+        SizeUnits::KB => value,
+        SizeUnits::MB => value * 1024.0,
+        SizeUnits::GB => value * 1024.0 * 1024.0,
+    }
+}
+
+fn rescale(value: f64, from: &SizeUnits, to: &SizeUnits) -> f64 {
+    let kb = to_kb(value, from);
+    match to {
+        SizeUnits::KB => kb,
+        SizeUnits::MB => kb / 1024.0,
+        SizeUnits::GB => kb / (1024.0 * 1024.0),
+    }
+}
+
+impl RecordedUpdate {
+    /// Returns the swap information with device sizes converted to `unit`.
+    #[cfg(target_os = "linux")]
+    pub fn swap_in(&self, unit: &SizeUnits) -> SwapUpdate {
+        let mut swap = self.swap.clone();
+        for device in &mut swap.swap_devices {
+            device.size_kb = rescale(device.size_kb, &self.unit, unit);
+            device.used_kb = rescale(device.used_kb, &self.unit, unit);
+        }
+        swap
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn swap_in(&self, _unit: &SizeUnits) -> SwapUpdate {
+        self.swap.clone()
+    }
+
+    /// Returns the processes with their swap size converted to `unit`.
+    pub fn processes_in(&self, unit: &SizeUnits) -> Vec<ProcessSwapInfo> {
+        self.processes
+            .iter()
+            .cloned()
+            .map(|mut process| {
+                process.swap_size = rescale(process.swap_size, &self.unit, unit);
+                process
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct Recorder {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    /// Opens `path` for appending, so several sessions can be recorded to one file.
+    pub fn create(path: &Path) -> Result<Self, RecordingError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| RecordingError::Io(path.to_owned(), e))?;
+        Ok(Self {
+            path: path.to_owned(),
+            writer: BufWriter::new(file),
+        })
+    }
+
+    pub fn record(
+        &mut self,
+        unit: &SizeUnits,
+        metric: SwapMetric,
+        swap: &SwapUpdate,
+        processes: &[ProcessSwapInfo],
+    ) -> Result<(), RecordingError> {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let update = RecordedUpdate {
+            timestamp_ms,
+            unit: unit.clone(),
+            metric,
+            swap: swap.clone(),
+            processes: processes.to_vec(),
+        };
+
+        serde_json::to_writer(&mut self.writer, &update)
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(self.writer))
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| RecordingError::Io(self.path.clone(), e))
+    }
+}
+
+#[derive(Debug)]
+pub struct Replay {
+    updates: Vec<RecordedUpdate>,
+    position: usize,
+    paused: bool,
+    speed: f64,
+    last_step: Instant,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self, RecordingError> {
+        let file = File::open(path).map_err(|e| RecordingError::Io(path.to_owned(), e))?;
+        let mut updates = Vec::new();
+
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| RecordingError::Io(path.to_owned(), e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let update = serde_json::from_str(&line)
+                .map_err(|e| RecordingError::Parse(number + 1, path.to_owned(), e))?;
+            updates.push(update);
+        }
+
+        if updates.is_empty() {
+            return Err(RecordingError::Empty(path.to_owned()));
+        }
+
+        Ok(Self {
+            updates,
+            position: 0,
+            paused: false,
+            speed: 1.0,
+            last_step: Instant::now(),
+        })
+    }
+
+    pub fn current(&self) -> &RecordedUpdate {
+        &self.updates[self.position]
+    }
+
+    /// Updates from the start of the recording up to and including the current one.
+    pub fn history(&self) -> &[RecordedUpdate] {
+        &self.updates[..=self.position]
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn update_count(&self) -> usize {
+        self.updates.len()
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Advances to the next update once the recorded delay, scaled by the speed,
+    /// has elapsed. Returns whether the position changed.
+    pub fn tick(&mut self) -> bool {
+        if self.paused || self.position + 1 >= self.updates.len() {
+            return false;
+        }
+
+        let recorded_delay = self.updates[self.position + 1]
+            .timestamp_ms
+            .saturating_sub(self.current().timestamp_ms)
+            .min(MAX_DELAY_MS);
+        let delay = Duration::from_millis(recorded_delay).div_f64(self.speed);
+
+        if self.last_step.elapsed() >= delay {
+            self.position += 1;
+            self.last_step = Instant::now();
+            return true;
+        }
+        false
+    }
+
+    pub fn step_forward(&mut self) -> bool {
+        self.last_step = Instant::now();
+        if self.position + 1 < self.updates.len() {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    pub fn step_back(&mut self) -> bool {
+        self.last_step = Instant::now();
+        if self.position > 0 {
+            self.position -= 1;
+            return true;
+        }
+        false
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.last_step = Instant::now();
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed * 2.0).min(MAX_SPEED);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = (self.speed / 2.0).max(MIN_SPEED);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, swap_size: f64) -> ProcessSwapInfo {
+        ProcessSwapInfo {
+            pid,
+            name: format!("p{pid}"),
+            swap_size,
+            user: String::new(),
+            uid: None,
+            ppid: 0,
+            parent: String::new(),
+            cgroup: String::new(),
+            systemd_unit: String::new(),
+            container_id: String::new(),
+            container_runtime: String::new(),
+        }
+    }
+
+    fn update(
+        timestamp_ms: u64,
+        unit: SizeUnits,
+        processes: Vec<ProcessSwapInfo>,
+    ) -> RecordedUpdate {
+        RecordedUpdate {
+            timestamp_ms,
+            unit,
+            metric: SwapMetric::VmSwap,
+            swap: SwapUpdate::default(),
+            processes,
+        }
+    }
+
+    /// A file in the temp directory that is removed again when dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!("swaptop-{}-{name}", std::process::id())))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn rescale_converts_between_units() {
+        assert_eq!(rescale(2.0, &SizeUnits::MB, &SizeUnits::KB), 2048.0);
+        assert_eq!(rescale(2048.0, &SizeUnits::KB, &SizeUnits::MB), 2.0);
+        assert_eq!(rescale(1.0, &SizeUnits::GB, &SizeUnits::MB), 1024.0);
+        assert_eq!(rescale(512.0, &SizeUnits::MB, &SizeUnits::GB), 0.5);
+        assert_eq!(rescale(3.5, &SizeUnits::MB, &SizeUnits::MB), 3.5);
+    }
+
+    #[test]
+    fn processes_are_shown_in_the_current_unit() {
+        let recorded = update(
+            0,
+            SizeUnits::KB,
+            vec![process(1, 1024.0), process(2, 512.0)],
+        );
+        let sizes: Vec<f64> = recorded
+            .processes_in(&SizeUnits::MB)
+            .iter()
+            .map(|p| p.swap_size)
+            .collect();
+        assert_eq!(sizes, [1.0, 0.5]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn devices_are_shown_in_the_current_unit() {
+        let mut recorded = update(0, SizeUnits::MB, Vec::new());
+        recorded.swap.total_swap = 4096;
+        recorded.swap.swap_devices = vec![crate::swap_info::InfoSwap {
+            name: "/swapfile".to_owned(),
+            kind: "file".to_owned(),
+            size_kb: 4.0,
+            used_kb: 1.0,
+            priority: -2,
+        }];
+        let swap = recorded.swap_in(&SizeUnits::KB);
+        assert_eq!(swap.swap_devices[0].size_kb, 4096.0);
+        assert_eq!(swap.swap_devices[0].used_kb, 1024.0);
+        // the totals are always recorded in KB
+        assert_eq!(swap.total_swap, 4096);
+    }
+
+    #[test]
+    fn replays_what_was_recorded() {
+        let file = TempFile::new("roundtrip.ndjson");
+        let mut recorder = Recorder::create(&file.0).unwrap();
+        let swap = SwapUpdate::default();
+        recorder
+            .record(
+                &SizeUnits::MB,
+                SwapMetric::SwapPss,
+                &swap,
+                &[process(7, 1.5)],
+            )
+            .unwrap();
+        recorder
+            .record(
+                &SizeUnits::MB,
+                SwapMetric::SwapPss,
+                &swap,
+                &[process(7, 2.5)],
+            )
+            .unwrap();
+        drop(recorder);
+
+        let mut replay = Replay::load(&file.0).unwrap();
+        assert_eq!(replay.update_count(), 2);
+        assert_eq!(replay.current().metric, SwapMetric::SwapPss);
+        assert_eq!(replay.current().processes[0].swap_size, 1.5);
+        assert!(replay.step_forward());
+        assert!(!replay.step_forward());
+        assert_eq!(replay.current().processes[0].swap_size, 2.5);
+        assert_eq!(replay.history().len(), 2);
+        assert!(replay.step_back());
+        assert_eq!(replay.position(), 0);
+    }
+
+    #[test]
+    fn load_reports_the_line_of_an_invalid_record() {
+        let file = TempFile::new("invalid.ndjson");
+        let valid = serde_json::to_string(&update(0, SizeUnits::KB, Vec::new())).unwrap();
+        std::fs::write(&file.0, format!("{valid}\n\n{{\"timestamp_ms\": 1}}\n")).unwrap();
+        assert!(matches!(
+            Replay::load(&file.0),
+            Err(RecordingError::Parse(3, _, _))
+        ));
+
+        std::fs::write(&file.0, "\n").unwrap();
+        assert!(matches!(
+            Replay::load(&file.0),
+            Err(RecordingError::Empty(_))
+        ));
+    }
+
+    #[test]
+    fn speed_stays_within_bounds() {
+        let file = TempFile::new("speed.ndjson");
+        let valid = serde_json::to_string(&update(0, SizeUnits::KB, Vec::new())).unwrap();
+        std::fs::write(&file.0, valid).unwrap();
+        let mut replay = Replay::load(&file.0).unwrap();
+        for _ in 0..10 {
+            replay.faster();
+        }
+        assert_eq!(replay.speed(), MAX_SPEED);
+        for _ in 0..20 {
+            replay.slower();
+        }
+        assert_eq!(replay.speed(), MIN_SPEED);
+    }
+}
@@ -0,0 +1,698 @@
+use serde::Serialize;
+use std::io::{self, Write};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(target_os = "linux")]
+use crate::pressure::MemoryPressure;
+use crate::swap_info::{
+    AggregateBy, ProcessSwapInfo, SizeUnits, SwapDataError, SwapMetric, SwapUpdate,
+    aggregate_processes, convert_swap, get_chart_info, get_processes_using_swap,
+};
+#[cfg(target_os = "linux")]
+use crate::swap_info::{InfoSwap, find_mount_device};
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Ndjson,
+    Csv,
+}
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub timestamp: u64,
+    pub hostname: String,
+    pub unit: SizeUnits,
+    pub metric: SwapMetric,
+    pub aggregated: bool,
+    pub group_by: Option<AggregateBy>,
+    pub swap: SwapUpdate,
+    pub processes: Vec<ProcessSwapInfo>,
+}
+
+impl Snapshot {
+    pub fn collect(
+        unit: SizeUnits,
+        metric: SwapMetric,
+        group_by: Option<AggregateBy>,
+        limit: Option<usize>,
+    ) -> Result<Self, SwapDataError> {
+        #[cfg(target_os = "linux")]
+        let swap = get_chart_info(unit.clone())?;
+        #[cfg(target_os = "windows")]
+        let swap = get_chart_info()?;
+
+        let mut processes = get_processes_using_swap(unit.clone(), metric)?;
+        processes.sort_by(|a, b| {
+            b.swap_size
+                .partial_cmp(&a.swap_size)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        if let Some(by) = group_by {
+            processes = aggregate_processes(processes, by);
+        }
+        if let Some(limit) = limit {
+            processes.truncate(limit);
+        }
+
+        Ok(Self {
+            timestamp: unix_timestamp(),
+            hostname: hostname(),
+            unit,
+            metric,
+            aggregated: group_by.is_some(),
+            group_by,
+            swap,
+            processes,
+        })
+    }
+}
+
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "linux")]
+pub fn hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|h| h.trim().to_owned())
+        .unwrap_or_else(|_| "unknown".into())
+}
+
+#[cfg(target_os = "windows")]
+pub fn hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_else(|_| "unknown".into())
+}
+
+/// Collects a snapshot and prints it to stdout in the requested format.
+/// A closed pipe (e.g. `swaptop --once | head`) is not treated as an error.
+pub fn print_report(
+    unit: SizeUnits,
+    metric: SwapMetric,
+    group_by: Option<AggregateBy>,
+    limit: Option<usize>,
+    format: OutputFormat,
+) -> color_eyre::Result<()> {
+    let snapshot = Snapshot::collect(unit, metric, group_by, limit)?;
+    let mut out = io::BufWriter::new(io::stdout().lock());
+
+    let written = match format {
+        OutputFormat::Text => write_text(&mut out, &snapshot),
+        OutputFormat::Json => write_json(&mut out, &snapshot),
+        OutputFormat::Ndjson => write_ndjson(&mut out, &snapshot),
+        OutputFormat::Csv => write_csv(&mut out, &snapshot),
+    };
+
+    match written.and_then(|_| out.flush()) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+/// Emits one record per `interval` until interrupted or `count` records were written.
+/// Output is flushed after every record so it can be followed through `tee` or `ssh`.
+pub fn stream_report(
+    unit: SizeUnits,
+    metric: SwapMetric,
+    group_by: Option<AggregateBy>,
+    limit: Option<usize>,
+    format: OutputFormat,
+    interval: Duration,
+    count: Option<u64>,
+) -> color_eyre::Result<()> {
+    let mut out = io::stdout().lock();
+    let mut emitted = 0;
+
+    if let OutputFormat::Csv = format
+        && let Err(e) = write_csv_header(&mut out).and_then(|_| out.flush())
+    {
+        return if e.kind() == io::ErrorKind::BrokenPipe {
+            Ok(())
+        } else {
+            Err(e.into())
+        };
+    }
+
+    while count.is_none_or(|count| emitted < count) {
+        let last_update = Instant::now();
+        let snapshot = Snapshot::collect(unit.clone(), metric, group_by, limit)?;
+
+        let written = match format {
+            OutputFormat::Text => write_text(&mut out, &snapshot).and_then(|_| writeln!(out)),
+            OutputFormat::Json | OutputFormat::Ndjson => write_ndjson(&mut out, &snapshot),
+            OutputFormat::Csv => write_csv_rows(&mut out, &snapshot),
+        };
+        match written.and_then(|_| out.flush()) {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            result => result?,
+        }
+
+        emitted += 1;
+        if count.is_none_or(|count| emitted < count) {
+            std::thread::sleep(interval.saturating_sub(last_update.elapsed()));
+        }
+    }
+
+    Ok(())
+}
+
+fn format_size(size: f64, unit: &SizeUnits) -> String {
+    match unit {
+        SizeUnits::KB => format!("{}", size),
+        _ => format!("{:.2}", size),
+    }
+}
+
+pub fn write_text(out: &mut impl Write, snapshot: &Snapshot) -> io::Result<()> {
+    let unit = &snapshot.unit;
+    let total = convert_swap(snapshot.swap.total_swap, unit.clone());
+    let used = convert_swap(snapshot.swap.used_swap, unit.clone());
+    let percent = if snapshot.swap.total_swap > 0 {
+        snapshot.swap.used_swap as f64 / snapshot.swap.total_swap as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    writeln!(
+        out,
+        "swap ({:?}) total: {} | used: {} | usage: {:.1}%",
+        unit,
+        format_size(total, unit),
+        format_size(used, unit),
+        percent
+    )?;
+
+    #[cfg(target_os = "linux")]
+    write_devices(out, snapshot)?;
+    #[cfg(target_os = "linux")]
+    write_zram(out, snapshot)?;
+    #[cfg(target_os = "linux")]
+    write_zswap(out, snapshot)?;
+    #[cfg(target_os = "linux")]
+    if let Some(pressure) = &snapshot.swap.pressure {
+        writeln!(out)?;
+        for (name, record) in [("some", &pressure.some), ("full", &pressure.full)] {
+            writeln!(
+                out,
+                "memory pressure {}: avg10={:.2}% avg60={:.2}% avg300={:.2}% total={}us",
+                name, record.avg10, record.avg60, record.avg300, record.total_us
+            )?;
+        }
+    }
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{:>12} | {:30} | {:>12}",
+        if snapshot.aggregated { "COUNT" } else { "PID" },
+        snapshot.group_by.map_or("PROCESS", AggregateBy::column),
+        snapshot.metric.used_column()
+    )?;
+    for process in &snapshot.processes {
+        writeln!(
+            out,
+            "{:>12} | {:30} | {:>12}",
+            process.pid,
+            process.name,
+            format_size(process.swap_size, unit)
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn write_devices(out: &mut impl Write, snapshot: &Snapshot) -> io::Result<()> {
+    let devices = &snapshot.swap.swap_devices;
+    if devices.is_empty() {
+        return Ok(());
+    }
+
+    let sources: Vec<String> = devices
+        .iter()
+        .map(|d| find_mount_device(std::path::Path::new(&d.name)).unwrap_or_else(|| "RAM".into()))
+        .collect();
+    let source_width = sources.iter().map(|s| s.len()).max().unwrap_or(4).max(4);
+    let name_width = devices
+        .iter()
+        .map(|d| d.name.len())
+        .max()
+        .unwrap_or(10)
+        .max(10);
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{:<source_width$} | {:<name_width$} | {:<10} | {:>8} | {:>12} | {:>12}",
+        "DISK", "PATH", "TYPE", "PRIORITY", "TOTAL", "USED"
+    )?;
+    for (device, source) in devices.iter().zip(&sources) {
+        writeln!(
+            out,
+            "{:<source_width$} | {:<name_width$} | {:<10} | {:>8} | {:>12} | {:>12}",
+            source,
+            device.name,
+            device.kind,
+            device.priority,
+            format_size(device.size_kb, &snapshot.unit),
+            format_size(device.used_kb, &snapshot.unit)
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn write_zram(out: &mut impl Write, snapshot: &Snapshot) -> io::Result<()> {
+    let devices = &snapshot.swap.zram_devices;
+    if devices.is_empty() {
+        return Ok(());
+    }
+    let size = |bytes: u64| {
+        format_size(
+            convert_swap(bytes / 1024, snapshot.unit.clone()),
+            &snapshot.unit,
+        )
+    };
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{:<11} | {:<8} | {:>12} | {:>12} | {:>12} | {:>12} | {:>6} | {:>8} | {:>8}",
+        "ZRAM", "ALGO", "DISK", "DATA", "COMPRESSED", "MEM USED", "RATIO", "SAME", "HUGE"
+    )?;
+    for device in devices {
+        writeln!(
+            out,
+            "{:<11} | {:<8} | {:>12} | {:>12} | {:>12} | {:>12} | {:>6.2} | {:>8} | {:>8}",
+            device.name,
+            device.algorithm,
+            size(device.disk_size),
+            size(device.orig_data_size),
+            size(device.compr_data_size),
+            size(device.mem_used_total),
+            device.compression_ratio(),
+            device.same_pages,
+            device.huge_pages
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn write_zswap(out: &mut impl Write, snapshot: &Snapshot) -> io::Result<()> {
+    let Some(zswap) = &snapshot.swap.zswap else {
+        return Ok(());
+    };
+    let size = |bytes: u64| {
+        format_size(
+            convert_swap(bytes / 1024, snapshot.unit.clone()),
+            &snapshot.unit,
+        )
+    };
+    let counter = |value: Option<u64>| value.map_or_else(|| "n/a".to_owned(), |v| v.to_string());
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "zswap ({}) compressor: {} | max pool: {}%",
+        if zswap.enabled { "enabled" } else { "disabled" },
+        zswap.compressor,
+        zswap.max_pool_percent
+    )?;
+    writeln!(
+        out,
+        "pool: {} | stored: {} | ratio: {:.2} | stored pages: {} | written back: {} | rejected: {} | pool limit hit: {}",
+        size(zswap.pool_size),
+        size(zswap.stored_size),
+        zswap.compression_ratio(),
+        counter(zswap.stored_pages),
+        counter(zswap.written_back_pages),
+        counter(zswap.rejected_pages),
+        counter(zswap.pool_limit_hit)
+    )
+}
+
+/// JSON form of a snapshot. Every size is given in `unit`, so none of the keys
+/// carry a unit of their own.
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    timestamp: u64,
+    hostname: &'a str,
+    unit: &'a SizeUnits,
+    metric: SwapMetric,
+    aggregated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group_by: Option<AggregateBy>,
+    total_swap: f64,
+    used_swap: f64,
+    #[cfg(target_os = "linux")]
+    swap_devices: &'a [InfoSwap],
+    #[cfg(target_os = "linux")]
+    zram_devices: Vec<JsonZram<'a>>,
+    #[cfg(target_os = "linux")]
+    zswap: Option<JsonZswap<'a>>,
+    #[cfg(target_os = "linux")]
+    swapped_in: f64,
+    #[cfg(target_os = "linux")]
+    swapped_out: f64,
+    #[cfg(target_os = "linux")]
+    pressure: Option<&'a MemoryPressure>,
+    processes: &'a [ProcessSwapInfo],
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Serialize)]
+struct JsonZram<'a> {
+    name: &'a str,
+    algorithm: &'a str,
+    disk_size: f64,
+    orig_data_size: f64,
+    compr_data_size: f64,
+    mem_used_total: f64,
+    same_pages: u64,
+    huge_pages: u64,
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Serialize)]
+struct JsonZswap<'a> {
+    enabled: bool,
+    compressor: &'a str,
+    max_pool_percent: u64,
+    pool_size: f64,
+    stored_size: f64,
+    stored_pages: Option<u64>,
+    written_back_pages: Option<u64>,
+    rejected_pages: Option<u64>,
+    pool_limit_hit: Option<u64>,
+}
+
+impl<'a> JsonReport<'a> {
+    fn new(snapshot: &'a Snapshot) -> Self {
+        let unit = &snapshot.unit;
+        let swap = &snapshot.swap;
+        #[cfg(target_os = "linux")]
+        let bytes = |value: u64| convert_swap(value, unit.clone()) / 1024.0;
+
+        Self {
+            timestamp: snapshot.timestamp,
+            hostname: &snapshot.hostname,
+            unit,
+            metric: snapshot.metric,
+            aggregated: snapshot.aggregated,
+            group_by: snapshot.group_by,
+            total_swap: convert_swap(swap.total_swap, unit.clone()),
+            used_swap: convert_swap(swap.used_swap, unit.clone()),
+            #[cfg(target_os = "linux")]
+            swap_devices: &swap.swap_devices,
+            #[cfg(target_os = "linux")]
+            zram_devices: swap
+                .zram_devices
+                .iter()
+                .map(|device| JsonZram {
+                    name: &device.name,
+                    algorithm: &device.algorithm,
+                    disk_size: bytes(device.disk_size),
+                    orig_data_size: bytes(device.orig_data_size),
+                    compr_data_size: bytes(device.compr_data_size),
+                    mem_used_total: bytes(device.mem_used_total),
+                    same_pages: device.same_pages,
+                    huge_pages: device.huge_pages,
+                })
+                .collect(),
+            #[cfg(target_os = "linux")]
+            zswap: swap.zswap.as_ref().map(|zswap| JsonZswap {
+                enabled: zswap.enabled,
+                compressor: &zswap.compressor,
+                max_pool_percent: zswap.max_pool_percent,
+                pool_size: bytes(zswap.pool_size),
+                stored_size: bytes(zswap.stored_size),
+                stored_pages: zswap.stored_pages,
+                written_back_pages: zswap.written_back_pages,
+                rejected_pages: zswap.rejected_pages,
+                pool_limit_hit: zswap.pool_limit_hit,
+            }),
+            #[cfg(target_os = "linux")]
+            swapped_in: convert_swap(swap.swapped_in_kb, unit.clone()),
+            #[cfg(target_os = "linux")]
+            swapped_out: convert_swap(swap.swapped_out_kb, unit.clone()),
+            #[cfg(target_os = "linux")]
+            pressure: swap.pressure.as_ref(),
+            processes: &snapshot.processes,
+        }
+    }
+}
+
+pub fn write_json(out: &mut impl Write, snapshot: &Snapshot) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, &JsonReport::new(snapshot))?;
+    writeln!(out)
+}
+
+pub fn write_ndjson(out: &mut impl Write, snapshot: &Snapshot) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, &JsonReport::new(snapshot))?;
+    writeln!(out)
+}
+
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn write_csv_header(out: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "timestamp,hostname,unit,record,id,name,priority,total,used"
+    )
+}
+
+/// Writes one row per record: the swap totals, each swap device and each process,
+/// all sizes in the snapshot unit.
+fn write_csv_rows(out: &mut impl Write, snapshot: &Snapshot) -> io::Result<()> {
+    let unit = &snapshot.unit;
+    let prefix = format!(
+        "{},{},{}",
+        snapshot.timestamp,
+        csv_field(&snapshot.hostname),
+        format!("{:?}", unit).to_lowercase()
+    );
+
+    writeln!(
+        out,
+        "{},total,,swap,,{},{}",
+        prefix,
+        convert_swap(snapshot.swap.total_swap, unit.clone()),
+        convert_swap(snapshot.swap.used_swap, unit.clone())
+    )?;
+
+    #[cfg(target_os = "linux")]
+    for device in &snapshot.swap.swap_devices {
+        writeln!(
+            out,
+            "{},device,,{},{},{},{}",
+            prefix,
+            csv_field(&device.name),
+            device.priority,
+            device.size_kb,
+            device.used_kb
+        )?;
+    }
+
+    let record = if snapshot.aggregated {
+        "group"
+    } else {
+        "process"
+    };
+    for process in &snapshot.processes {
+        writeln!(
+            out,
+            "{},{},{},{},,,{}",
+            prefix,
+            record,
+            process.pid,
+            csv_field(&process.name),
+            process.swap_size
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn write_csv(out: &mut impl Write, snapshot: &Snapshot) -> io::Result<()> {
+    write_csv_header(out)?;
+    write_csv_rows(out, snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, swap_size: f64) -> ProcessSwapInfo {
+        ProcessSwapInfo {
+            pid,
+            name: name.to_owned(),
+            swap_size,
+            user: String::new(),
+            uid: None,
+            ppid: 0,
+            parent: String::new(),
+            cgroup: String::new(),
+            systemd_unit: String::new(),
+            container_id: String::new(),
+            container_runtime: String::new(),
+        }
+    }
+
+    // the update is needless on Windows, where SwapUpdate only has the totals
+    #[cfg_attr(target_os = "windows", allow(clippy::needless_update))]
+    fn snapshot(group_by: Option<AggregateBy>, processes: Vec<ProcessSwapInfo>) -> Snapshot {
+        Snapshot {
+            timestamp: 1700000000,
+            hostname: "web,1".to_owned(),
+            unit: SizeUnits::MB,
+            metric: SwapMetric::VmSwap,
+            aggregated: group_by.is_some(),
+            group_by,
+            swap: SwapUpdate {
+                #[cfg(target_os = "linux")]
+                swap_devices: vec![InfoSwap {
+                    name: "/swap file".to_owned(),
+                    kind: "file".to_owned(),
+                    size_kb: 2.0,
+                    used_kb: 0.5,
+                    priority: -2,
+                }],
+                total_swap: 2048,
+                used_swap: 512,
+                ..SwapUpdate::default()
+            },
+            processes,
+        }
+    }
+
+    fn csv_rows(snapshot: &Snapshot) -> Vec<String> {
+        let mut out = Vec::new();
+        write_csv_rows(&mut out, snapshot).unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(str::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn csv_field_quotes_separators_quotes_and_newlines() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field(r#"say "hi""#), r#""say ""hi""""#);
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_field("carriage\rreturn"), "\"carriage\rreturn\"");
+    }
+
+    #[test]
+    fn csv_rows_share_the_header_columns() {
+        let mut out = Vec::new();
+        write_csv_header(&mut out).unwrap();
+        let columns = String::from_utf8(out)
+            .unwrap()
+            .trim_end()
+            .split(',')
+            .count();
+
+        let rows = csv_rows(&snapshot(None, vec![process(42, "worker", 1.5)]));
+        assert_eq!(rows[0], "1700000000,\"web,1\",mb,total,,swap,,2,0.5");
+        #[cfg(target_os = "linux")]
+        assert_eq!(
+            rows[1],
+            "1700000000,\"web,1\",mb,device,,/swap file,-2,2,0.5"
+        );
+        assert_eq!(
+            rows.last().unwrap(),
+            "1700000000,\"web,1\",mb,process,42,worker,,,1.5"
+        );
+        for row in &rows {
+            // the quoted hostname holds one separator
+            assert_eq!(row.split(',').count() - 1, columns, "{row}");
+        }
+    }
+
+    #[test]
+    fn csv_rows_escape_process_names() {
+        let rows = csv_rows(&snapshot(
+            None,
+            vec![process(1, "a,b", 1.0), process(2, r#"say "hi""#, 2.0)],
+        ));
+        let processes = &rows[rows.len() - 2..];
+        assert_eq!(
+            processes[0],
+            "1700000000,\"web,1\",mb,process,1,\"a,b\",,,1"
+        );
+        assert_eq!(
+            processes[1],
+            r#"1700000000,"web,1",mb,process,2,"say ""hi""",,,2"#
+        );
+
+        let mut out = Vec::new();
+        let multiline = snapshot(None, vec![process(3, "two\nlines", 3.0)]);
+        write_csv_rows(&mut out, &multiline).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.ends_with("process,3,\"two\nlines\",,,3\n"), "{text}");
+    }
+
+    #[test]
+    fn csv_rows_mark_aggregated_groups() {
+        let rows = csv_rows(&snapshot(
+            Some(AggregateBy::Name),
+            vec![process(3, "python3", 64.0)],
+        ));
+        assert_eq!(
+            rows.last().unwrap(),
+            "1700000000,\"web,1\",mb,group,3,python3,,,64"
+        );
+        assert!(!rows.iter().any(|row| row.contains(",process,")));
+    }
+
+    #[test]
+    fn json_reports_every_size_in_the_unit() {
+        let snapshot = snapshot(None, vec![process(42, "worker", 1.5)]);
+        #[cfg(target_os = "linux")]
+        let snapshot = {
+            let mut snapshot = snapshot;
+            snapshot.swap.swapped_in_kb = 3072;
+            snapshot.swap.zram_devices = vec![crate::zram::ZramDevice {
+                name: "/dev/zram0".to_owned(),
+                disk_size: 4 << 20,
+                mem_used_total: 512 << 10,
+                ..Default::default()
+            }];
+            snapshot
+        };
+        let mut out = Vec::new();
+        write_ndjson(&mut out, &snapshot).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(json["unit"], "mb");
+        assert_eq!(json["total_swap"], 2.0);
+        assert_eq!(json["used_swap"], 0.5);
+        assert_eq!(json["processes"][0]["swap_size"], 1.5);
+        #[cfg(target_os = "linux")]
+        {
+            assert_eq!(json["swap_devices"][0]["size"], 2.0);
+            assert_eq!(json["swapped_in"], 3.0);
+            assert_eq!(json["zram_devices"][0]["disk_size"], 4.0);
+            assert_eq!(json["zram_devices"][0]["mem_used_total"], 0.5);
+        }
+        let keys = json.as_object().unwrap().keys();
+        assert!(!keys.into_iter().any(|key| key.ends_with("_kb")));
+    }
+}
@@ -0,0 +1,144 @@
+use std::io;
+use thiserror::Error;
+
+/// Signals offered by the signal picker, in the order they are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Term,
+    Kill,
+    Hup,
+    Int,
+    Stop,
+    Cont,
+    Usr1,
+    Usr2,
+}
+
+impl Signal {
+    pub const ALL: [Signal; 8] = [
+        Signal::Term,
+        Signal::Kill,
+        Signal::Hup,
+        Signal::Int,
+        Signal::Stop,
+        Signal::Cont,
+        Signal::Usr1,
+        Signal::Usr2,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Signal::Term => "SIGTERM",
+            Signal::Kill => "SIGKILL",
+            Signal::Hup => "SIGHUP",
+            Signal::Int => "SIGINT",
+            Signal::Stop => "SIGSTOP",
+            Signal::Cont => "SIGCONT",
+            Signal::Usr1 => "SIGUSR1",
+            Signal::Usr2 => "SIGUSR2",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Signal::Term => "ask the process to terminate",
+            Signal::Kill => "kill the process, cannot be handled",
+            Signal::Hup => "hangup, many daemons reload",
+            Signal::Int => "interrupt, like Ctrl+C",
+            Signal::Stop => "pause the process",
+            Signal::Cont => "resume a paused process",
+            Signal::Usr1 => "user defined signal 1",
+            Signal::Usr2 => "user defined signal 2",
+        }
+    }
+
+    fn number(self) -> libc::c_int {
+        match self {
+            Signal::Term => libc::SIGTERM,
+            Signal::Kill => libc::SIGKILL,
+            Signal::Hup => libc::SIGHUP,
+            Signal::Int => libc::SIGINT,
+            Signal::Stop => libc::SIGSTOP,
+            Signal::Cont => libc::SIGCONT,
+            Signal::Usr1 => libc::SIGUSR1,
+            Signal::Usr2 => libc::SIGUSR2,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SignalError {
+    #[error("permission denied for PID {0}")]
+    PermissionDenied(u32),
+    #[error("PID {0} no longer exists")]
+    NoProcess(u32),
+    #[error("PID {0}: {1}")]
+    Io(u32, io::Error),
+}
+
+pub fn send(pid: u32, signal: Signal) -> Result<(), SignalError> {
+    // PIDs above i32::MAX would address process groups
+    let Ok(target) = libc::pid_t::try_from(pid) else {
+        return Err(SignalError::NoProcess(pid));
+    };
+    if unsafe { libc::kill(target, signal.number()) } == 0 {
+        return Ok(());
+    }
+    let error = io::Error::last_os_error();
+    Err(match error.raw_os_error() {
+        Some(libc::EPERM) => SignalError::PermissionDenied(pid),
+        Some(libc::ESRCH) => SignalError::NoProcess(pid),
+        _ => SignalError::Io(pid, error),
+    })
+}
+
+/// State of the signal picker. `targets` holds PID and name of every process
+/// the signal goes to, one for a process row, all members for a group.
+#[derive(Debug)]
+pub struct SignalDialog {
+    pub title: String,
+    pub targets: Vec<(u32, String)>,
+    pub selected: usize,
+    pub confirming: bool,
+    pub error: Option<String>,
+}
+
+impl SignalDialog {
+    pub fn new(title: String, targets: Vec<(u32, String)>) -> Self {
+        Self {
+            title,
+            targets,
+            selected: 0,
+            confirming: false,
+            error: None,
+        }
+    }
+
+    pub fn signal(&self) -> Signal {
+        Signal::ALL[self.selected]
+    }
+
+    /// Sends the selected signal to every target and returns how many received
+    /// it. Failures are kept in `error` so the dialog can show them.
+    pub fn send(&mut self) -> usize {
+        let signal = self.signal();
+        let mut errors = Vec::new();
+        for (pid, _) in &self.targets {
+            if let Err(e) = send(*pid, signal) {
+                errors.push(e.to_string());
+            }
+        }
+        let sent = self.targets.len() - errors.len();
+        self.error = match errors.len() {
+            0 => None,
+            1 => errors.pop(),
+            n => Some(format!(
+                "{} of {} failed, {}",
+                n,
+                self.targets.len(),
+                errors[0]
+            )),
+        };
+        sent
+    }
+}
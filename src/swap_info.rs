@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use thiserror::Error;
 
@@ -9,32 +10,239 @@ use proc_mounts::SwapIter;
 #[cfg(target_os = "linux")]
 use procfs::{self, Current, Meminfo};
 
-#[derive(Debug, Clone)]
+#[cfg(target_os = "linux")]
+use crate::containers::attribute;
+#[cfg(target_os = "linux")]
+use crate::pressure::{MemoryPressure, get_memory_pressure};
+#[cfg(target_os = "linux")]
+use crate::zram::{ZramDevice, get_zram_devices};
+#[cfg(target_os = "linux")]
+use crate::zswap::{ZswapStats, get_zswap_stats};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessSwapInfo {
     pub pid: u32,
     pub name: String,
     pub swap_size: f64,
+    #[serde(default)]
+    pub user: String,
+    #[serde(default)]
+    pub uid: Option<u32>,
+    #[serde(default)]
+    pub ppid: u32,
+    #[serde(default)]
+    pub parent: String,
+    #[serde(default)]
+    pub cgroup: String,
+    #[serde(default)]
+    pub systemd_unit: String,
+    #[serde(default)]
+    pub container_id: String,
+    #[serde(default)]
+    pub container_runtime: String,
+}
+
+impl ProcessSwapInfo {
+    /// `runtime:id` of the container the process runs in, empty outside containers.
+    pub fn container(&self) -> String {
+        if self.container_id.is_empty() {
+            String::new()
+        } else {
+            format!("{}:{}", self.container_runtime, self.container_id)
+        }
+    }
 }
 
 #[cfg(target_os = "linux")]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InfoSwap {
     pub name: String,
     pub kind: String,
+    #[serde(rename = "size")]
     pub size_kb: f64,
+    #[serde(rename = "used")]
     pub used_kb: f64,
     pub priority: isize,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SwapUpdate {
     #[cfg(target_os = "linux")]
+    #[serde(default)]
     pub swap_devices: Vec<InfoSwap>,
+    #[cfg(target_os = "linux")]
+    #[serde(default)]
+    pub zram_devices: Vec<ZramDevice>,
+    #[cfg(target_os = "linux")]
+    #[serde(default)]
+    pub zswap: Option<ZswapStats>,
+    /// Cumulative `pswpin`/`pswpout` from `/proc/vmstat`, converted from pages.
+    #[cfg(target_os = "linux")]
+    #[serde(default)]
+    pub swapped_in_kb: u64,
+    #[cfg(target_os = "linux")]
+    #[serde(default)]
+    pub swapped_out_kb: u64,
+    #[cfg(target_os = "linux")]
+    #[serde(default)]
+    pub pressure: Option<MemoryPressure>,
+    #[serde(rename = "total_swap_kb")]
     pub total_swap: u64,
+    #[serde(rename = "used_swap_kb")]
     pub used_swap: u64,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortColumn {
+    Pid,
+    Name,
+    #[default]
+    Swap,
+    Unit,
+    Container,
+}
+
+impl SortColumn {
+    pub fn next(self) -> Self {
+        match self {
+            SortColumn::Pid => SortColumn::Name,
+            SortColumn::Name => SortColumn::Swap,
+            SortColumn::Swap => SortColumn::Unit,
+            SortColumn::Unit => SortColumn::Container,
+            SortColumn::Container => SortColumn::Pid,
+        }
+    }
+
+    /// Whether the column only exists while the unit and container columns are shown.
+    pub fn is_attribution(self) -> bool {
+        matches!(self, SortColumn::Unit | SortColumn::Container)
+    }
+
+    /// Sizes read best largest first, identifiers in ascending order.
+    pub fn default_descending(self) -> bool {
+        matches!(self, SortColumn::Swap)
+    }
+}
+
+/// Which per-process figure is reported as swap usage. `VmSwap` counts every
+/// swapped page a process maps, `SwapPss` divides shared pages between their users.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SwapMetric {
+    #[default]
+    #[value(name = "vmswap")]
+    VmSwap,
+    #[value(name = "swappss")]
+    SwapPss,
+}
+
+impl SwapMetric {
+    pub fn toggle(self) -> Self {
+        match self {
+            SwapMetric::VmSwap => SwapMetric::SwapPss,
+            SwapMetric::SwapPss => SwapMetric::VmSwap,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SwapMetric::VmSwap => "VmSwap",
+            SwapMetric::SwapPss => "SwapPss",
+        }
+    }
+
+    /// Header of the per-process swap column.
+    #[cfg(target_os = "linux")]
+    pub fn used_column(self) -> String {
+        format!("USED ({})", self.label())
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn used_column(self) -> String {
+        "USED".to_owned()
+    }
+}
+
+/// Key used to group processes in aggregate mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AggregateBy {
+    #[default]
+    Name,
+    User,
+    Uid,
+    Parent,
+    Cgroup,
+    Unit,
+    Container,
+}
+
+impl AggregateBy {
+    #[cfg(target_os = "linux")]
+    pub fn next(self) -> Self {
+        match self {
+            AggregateBy::Name => AggregateBy::User,
+            AggregateBy::User => AggregateBy::Uid,
+            AggregateBy::Uid => AggregateBy::Parent,
+            AggregateBy::Parent => AggregateBy::Cgroup,
+            AggregateBy::Cgroup => AggregateBy::Unit,
+            AggregateBy::Unit => AggregateBy::Container,
+            AggregateBy::Container => AggregateBy::Name,
+        }
+    }
+
+    /// UIDs, cgroups, units and containers do not exist on Windows.
+    #[cfg(target_os = "windows")]
+    pub fn next(self) -> Self {
+        match self {
+            AggregateBy::Name => AggregateBy::User,
+            AggregateBy::User => AggregateBy::Parent,
+            _ => AggregateBy::Name,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AggregateBy::Name => "name",
+            AggregateBy::User => "user",
+            AggregateBy::Uid => "uid",
+            AggregateBy::Parent => "parent",
+            AggregateBy::Cgroup => "cgroup",
+            AggregateBy::Unit => "unit",
+            AggregateBy::Container => "container",
+        }
+    }
+
+    /// Header of the column holding the aggregation key.
+    pub fn column(self) -> &'static str {
+        match self {
+            AggregateBy::Name => "PROCESS",
+            AggregateBy::User => "USER",
+            AggregateBy::Uid => "UID",
+            AggregateBy::Parent => "PARENT",
+            AggregateBy::Cgroup => "CGROUP",
+            AggregateBy::Unit => "UNIT",
+            AggregateBy::Container => "CONTAINER",
+        }
+    }
+
+    pub fn key(self, process: &ProcessSwapInfo) -> String {
+        let key = match self {
+            AggregateBy::Name => process.name.clone(),
+            AggregateBy::User => process.user.clone(),
+            AggregateBy::Uid => process.uid.map(|uid| uid.to_string()).unwrap_or_default(),
+            AggregateBy::Parent if process.ppid == 0 => String::new(),
+            AggregateBy::Parent => format!("{} ({})", process.ppid, process.parent),
+            AggregateBy::Cgroup => process.cgroup.clone(),
+            AggregateBy::Unit => process.systemd_unit.clone(),
+            AggregateBy::Container => process.container(),
+        };
+        if key.is_empty() { "-".to_owned() } else { key }
+    }
+}
+
+#[derive(Debug, Clone, Default, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SizeUnits {
     #[default]
     KB,
@@ -49,6 +257,8 @@ pub enum SwapDataError {
     Procfs(#[from] procfs::ProcError),
     #[error("I/O error accessing /proc: {0}")]
     Io(#[from] std::io::Error),
+    #[error("process {0} exited")]
+    ProcessExited(u32),
 }
 
 #[cfg(target_os = "windows")]
@@ -60,8 +270,18 @@ pub enum SwapDataError {
 
 #[cfg(target_os = "linux")]
 pub fn get_swap_devices(unit: SizeUnits) -> std::io::Result<Vec<InfoSwap>> {
+    let swaps = std::fs::File::open("/proc/swaps")?;
+    read_swap_devices(std::io::BufReader::new(swaps), unit)
+}
+
+/// Parses the `/proc/swaps` table.
+#[cfg(target_os = "linux")]
+pub fn read_swap_devices(
+    reader: impl std::io::BufRead,
+    unit: SizeUnits,
+) -> std::io::Result<Vec<InfoSwap>> {
     let mut out = Vec::new();
-    for swap in SwapIter::new()? {
+    for swap in SwapIter::new_from_reader(reader)? {
         let s = swap?;
         out.push(InfoSwap {
             name: s.source.to_string_lossy().into_owned(),
@@ -75,24 +295,58 @@ pub fn get_swap_devices(unit: SizeUnits) -> std::io::Result<Vec<InfoSwap>> {
 }
 
 #[cfg(target_os = "linux")]
-pub fn get_processes_using_swap(unit: SizeUnits) -> Result<Vec<ProcessSwapInfo>, SwapDataError> {
+pub fn get_processes_using_swap(
+    unit: SizeUnits,
+    metric: SwapMetric,
+) -> Result<Vec<ProcessSwapInfo>, SwapDataError> {
     let mut swap_processes = Vec::new();
+    let users = read_users();
+    let mut parents: HashMap<i32, String> = HashMap::new();
 
     for process in (procfs::process::all_processes()?).flatten() {
         let pid = process.pid;
+        // a process without VmSwap has no SwapPss either, so the cheap status read
+        // filters out everything that does not need smaps_rollup
         if let Ok(status) = process.status()
-            && let Some(swap_kb) = status.vmswap
-            && swap_kb > 0
+            && let Some(vmswap_kb) = status.vmswap
+            && vmswap_kb > 0
+            && let Some(swap_kb) = match metric {
+                SwapMetric::VmSwap => Some(vmswap_kb),
+                SwapMetric::SwapPss => read_swap_pss(pid),
+            }
         {
             let name = match process.stat() {
                 Ok(stat) => stat.comm,
                 Err(_) => "unknown".to_string(),
             };
+            let parent = parents
+                .entry(status.ppid)
+                .or_insert_with(|| {
+                    procfs::process::Process::new(status.ppid)
+                        .and_then(|p| p.stat())
+                        .map(|stat| stat.comm)
+                        .unwrap_or_else(|_| "unknown".to_string())
+                })
+                .clone();
             let swap_size = convert_swap(swap_kb, unit.clone());
+            let user = users
+                .get(&status.ruid)
+                .cloned()
+                .unwrap_or_else(|| status.ruid.to_string());
+            let cgroups = process.cgroups().map(|c| c.0).unwrap_or_default();
+            let attribution = attribute(cgroups.iter().map(|c| c.pathname.as_str()));
             let info = ProcessSwapInfo {
                 pid: pid as u32,
                 name,
                 swap_size,
+                user,
+                uid: Some(status.ruid),
+                ppid: status.ppid as u32,
+                parent,
+                cgroup: memory_cgroup(&cgroups).unwrap_or_default(),
+                systemd_unit: attribution.systemd_unit,
+                container_id: attribution.container_id,
+                container_runtime: attribution.container_runtime,
             };
             swap_processes.push(info);
         }
@@ -101,6 +355,51 @@ pub fn get_processes_using_swap(unit: SizeUnits) -> Result<Vec<ProcessSwapInfo>,
     Ok(swap_processes)
 }
 
+/// Reads `SwapPss` in KB from `/proc/PID/smaps_rollup`. Reading the file of
+/// another user's process requires root, those processes are skipped.
+#[cfg(target_os = "linux")]
+fn read_swap_pss(pid: i32) -> Option<u64> {
+    let rollup = std::fs::read_to_string(format!("/proc/{pid}/smaps_rollup")).ok()?;
+    rollup.lines().find_map(|line| {
+        line.strip_prefix("SwapPss:")?
+            .trim()
+            .trim_end_matches("kB")
+            .trim()
+            .parse()
+            .ok()
+    })
+}
+
+/// Picks the memory cgroup of a process: its v1 memory controller path when
+/// that controller is on a v1 hierarchy, otherwise its cgroup v2 path.
+#[cfg(target_os = "linux")]
+pub fn memory_cgroup(cgroups: &[procfs::ProcessCGroup]) -> Option<String> {
+    cgroups
+        .iter()
+        .find(|c| c.controllers.iter().any(|c| c == "memory"))
+        .or_else(|| cgroups.iter().find(|c| c.hierarchy == 0))
+        .or(cgroups.first())
+        .map(|c| c.pathname.clone())
+}
+
+/// Maps UIDs to user names from `/etc/passwd`.
+#[cfg(target_os = "linux")]
+pub fn read_users() -> HashMap<u32, String> {
+    let Ok(passwd) = std::fs::read_to_string("/etc/passwd") else {
+        return HashMap::new();
+    };
+
+    passwd
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_owned()))
+        })
+        .collect()
+}
+
 #[cfg(target_os = "linux")]
 pub fn find_mount_device(path: &std::path::Path) -> Option<String> {
     let abs_path = path.canonicalize().ok()?;
@@ -114,7 +413,13 @@ pub fn find_mount_device(path: &std::path::Path) -> Option<String> {
         .filter(|m| abs_path.starts_with(&m.mount_point))
         .max_by_key(|m| m.mount_point.components().count())?;
 
-    Some(if best_mount.fs_type == "devtmpfs" {
+    let is_zram = abs_path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with("zram"));
+
+    Some(if best_mount.fs_type == "devtmpfs" && is_zram {
+        "zram".to_owned()
+    } else if best_mount.fs_type == "devtmpfs" {
         "RAM".to_owned()
     } else {
         best_mount.mount_source?
@@ -122,17 +427,35 @@ pub fn find_mount_device(path: &std::path::Path) -> Option<String> {
 }
 
 #[cfg(target_os = "windows")]
-pub fn get_processes_using_swap(unit: SizeUnits) -> Result<Vec<ProcessSwapInfo>, SwapDataError> {
+pub fn get_processes_using_swap(
+    unit: SizeUnits,
+    _metric: SwapMetric,
+) -> Result<Vec<ProcessSwapInfo>, SwapDataError> {
     let mut profile_page_processes = Vec::new();
 
     if let Ok(tasks) = tasklist::Tasklist::new() {
+        let tasks: Vec<_> = tasks.collect();
+        let names: HashMap<u32, String> = tasks.iter().map(|t| (t.pid, t.pname.clone())).collect();
         for task in tasks {
             let meminfo = task.get_memory_info();
+            let user = task.get_user().unwrap_or_default();
+            let ppid = task.get_parrent().unwrap_or_default();
 
             let info = ProcessSwapInfo {
                 pid: task.pid,
                 name: task.pname,
                 swap_size: convert_swap(meminfo.get_pagefile_usage() as u64 / 1024, unit.clone()),
+                user,
+                uid: None,
+                ppid,
+                parent: names
+                    .get(&ppid)
+                    .cloned()
+                    .unwrap_or_else(|| "unknown".to_string()),
+                cgroup: String::new(),
+                systemd_unit: String::new(),
+                container_id: String::new(),
+                container_runtime: String::new(),
             };
             profile_page_processes.push(info);
         }
@@ -148,8 +471,17 @@ pub fn get_chart_info(unit: SizeUnits) -> Result<SwapUpdate, SwapDataError> {
     let total_swap_kb = meminfo.swap_total / 1024;
     let used_swap_kb = meminfo.swap_total.saturating_sub(meminfo.swap_free) / 1024;
 
+    let vmstat = procfs::vmstat()?;
+    let page_kb = procfs::page_size() / 1024;
+    let pages_kb = |name: &str| vmstat.get(name).copied().unwrap_or(0).max(0) as u64 * page_kb;
+
     Ok(SwapUpdate {
         swap_devices: get_swap_devices(unit)?,
+        zram_devices: get_zram_devices(),
+        zswap: get_zswap_stats(meminfo.z_swap, meminfo.z_swapped),
+        swapped_in_kb: pages_kb("pswpin"),
+        swapped_out_kb: pages_kb("pswpout"),
+        pressure: get_memory_pressure(),
         total_swap: total_swap_kb,
         used_swap: used_swap_kb,
     })
@@ -192,21 +524,32 @@ pub fn convert_swap(kb: u64, unit: SizeUnits) -> f64 {
     }
 }
 
-pub fn aggregate_processes(processes: Vec<ProcessSwapInfo>) -> Vec<ProcessSwapInfo> {
-    let mut name_to_info: HashMap<String, (f64, u32)> = HashMap::new();
+pub fn aggregate_processes(
+    processes: Vec<ProcessSwapInfo>,
+    by: AggregateBy,
+) -> Vec<ProcessSwapInfo> {
+    let mut key_to_info: HashMap<String, (f64, u32)> = HashMap::new();
 
     for process in processes {
-        let entry = name_to_info.entry(process.name).or_insert((0.0, 0));
+        let entry = key_to_info.entry(by.key(&process)).or_insert((0.0, 0));
         entry.0 += process.swap_size;
         entry.1 += 1;
     }
 
-    let mut aggregated_processes: Vec<ProcessSwapInfo> = name_to_info
+    let mut aggregated_processes: Vec<ProcessSwapInfo> = key_to_info
         .into_iter()
-        .map(|(name, (swap_size, count))| ProcessSwapInfo {
+        .map(|(key, (swap_size, count))| ProcessSwapInfo {
             pid: count,
-            name,
+            name: key,
             swap_size,
+            user: String::new(),
+            uid: None,
+            ppid: 0,
+            parent: String::new(),
+            cgroup: String::new(),
+            systemd_unit: String::new(),
+            container_id: String::new(),
+            container_runtime: String::new(),
         })
         .collect();
 
@@ -217,3 +560,204 @@ pub fn aggregate_processes(processes: Vec<ProcessSwapInfo>) -> Vec<ProcessSwapIn
     });
     aggregated_processes
 }
+
+pub fn sort_processes(processes: &mut [ProcessSwapInfo], column: SortColumn, descending: bool) {
+    processes.sort_by(|a, b| {
+        let ordering = match column {
+            SortColumn::Pid => a.pid.cmp(&b.pid),
+            SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortColumn::Swap => a
+                .swap_size
+                .partial_cmp(&b.swap_size)
+                .unwrap_or(std::cmp::Ordering::Equal),
+            SortColumn::Unit => a.systemd_unit.cmp(&b.systemd_unit),
+            SortColumn::Container => a.container().cmp(&b.container()),
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, swap_size: f64) -> ProcessSwapInfo {
+        ProcessSwapInfo {
+            pid,
+            name: name.to_owned(),
+            swap_size,
+            user: String::new(),
+            uid: None,
+            ppid: 0,
+            parent: String::new(),
+            cgroup: String::new(),
+            systemd_unit: String::new(),
+            container_id: String::new(),
+            container_runtime: String::new(),
+        }
+    }
+
+    fn pids(processes: &[ProcessSwapInfo]) -> Vec<u32> {
+        processes.iter().map(|p| p.pid).collect()
+    }
+
+    #[test]
+    fn sorts_by_each_column() {
+        let mut processes = vec![
+            process(30, "beta", 2.0),
+            process(10, "Gamma", 8.0),
+            process(20, "alpha", 4.0),
+        ];
+        processes[0].systemd_unit = "b.service".into();
+        processes[1].systemd_unit = "a.service".into();
+        processes[2].container_runtime = "docker".into();
+        processes[2].container_id = "abc".into();
+
+        sort_processes(&mut processes, SortColumn::Pid, false);
+        assert_eq!(pids(&processes), [10, 20, 30]);
+        sort_processes(&mut processes, SortColumn::Swap, true);
+        assert_eq!(pids(&processes), [10, 20, 30]);
+        sort_processes(&mut processes, SortColumn::Swap, false);
+        assert_eq!(pids(&processes), [30, 20, 10]);
+        // names compare case-insensitively
+        sort_processes(&mut processes, SortColumn::Name, false);
+        assert_eq!(pids(&processes), [20, 30, 10]);
+        sort_processes(&mut processes, SortColumn::Name, true);
+        assert_eq!(pids(&processes), [10, 30, 20]);
+        // processes outside a unit or container sort first
+        sort_processes(&mut processes, SortColumn::Unit, false);
+        assert_eq!(pids(&processes), [20, 10, 30]);
+        sort_processes(&mut processes, SortColumn::Container, false);
+        assert_eq!(pids(&processes)[2], 20);
+    }
+
+    #[test]
+    fn sorting_keeps_the_order_of_equal_rows() {
+        let mut processes = vec![
+            process(1, "same", 1.0),
+            process(2, "same", f64::NAN),
+            process(3, "same", 1.0),
+        ];
+        sort_processes(&mut processes, SortColumn::Name, true);
+        assert_eq!(pids(&processes), [1, 2, 3]);
+        sort_processes(&mut processes, SortColumn::Swap, true);
+        assert_eq!(pids(&processes), [1, 2, 3]);
+    }
+
+    #[test]
+    fn sort_columns_cycle_through_every_column() {
+        let mut column = SortColumn::default();
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            seen.push(column);
+            column = column.next();
+        }
+        assert_eq!(column, SortColumn::default());
+        assert_eq!(
+            seen,
+            [
+                SortColumn::Swap,
+                SortColumn::Unit,
+                SortColumn::Container,
+                SortColumn::Pid,
+                SortColumn::Name,
+            ]
+        );
+        assert!(SortColumn::Swap.default_descending());
+        assert!(!SortColumn::Name.default_descending());
+    }
+
+    fn groups(processes: Vec<ProcessSwapInfo>, by: AggregateBy) -> Vec<(String, u32, f64)> {
+        aggregate_processes(processes, by)
+            .into_iter()
+            .map(|group| (group.name, group.pid, group.swap_size))
+            .collect()
+    }
+
+    #[test]
+    fn aggregates_by_name_largest_first() {
+        let processes = vec![
+            process(1, "python3", 10.0),
+            process(2, "bash", 1.0),
+            process(3, "python3", 20.0),
+        ];
+        // the PID column of a group holds its process count
+        assert_eq!(
+            groups(processes, AggregateBy::Name),
+            [("python3".into(), 2, 30.0), ("bash".into(), 1, 1.0)]
+        );
+    }
+
+    #[test]
+    fn aggregates_by_user_uid_and_parent() {
+        let mut processes = vec![
+            process(1, "a", 4.0),
+            process(2, "b", 2.0),
+            process(3, "c", 1.0),
+        ];
+        for (process, (user, uid, ppid)) in
+            processes
+                .iter_mut()
+                .zip([("root", 0, 1), ("alice", 1000, 1), ("", 0, 0)])
+        {
+            process.user = user.into();
+            process.uid = (!user.is_empty()).then_some(uid);
+            process.ppid = ppid;
+            process.parent = if ppid == 1 {
+                "systemd".into()
+            } else {
+                String::new()
+            };
+        }
+
+        assert_eq!(
+            groups(processes.clone(), AggregateBy::User),
+            [
+                ("root".into(), 1, 4.0),
+                ("alice".into(), 1, 2.0),
+                ("-".into(), 1, 1.0)
+            ]
+        );
+        assert_eq!(
+            groups(processes.clone(), AggregateBy::Uid),
+            [
+                ("0".into(), 1, 4.0),
+                ("1000".into(), 1, 2.0),
+                ("-".into(), 1, 1.0)
+            ]
+        );
+        assert_eq!(
+            groups(processes, AggregateBy::Parent),
+            [("1 (systemd)".into(), 2, 6.0), ("-".into(), 1, 1.0)]
+        );
+    }
+
+    #[test]
+    fn aggregates_by_attribution() {
+        let mut processes = vec![process(1, "a", 1.0), process(2, "b", 2.0)];
+        processes[0].cgroup = "/system.slice/nginx.service".into();
+        processes[0].systemd_unit = "nginx.service".into();
+        processes[1].container_runtime = "docker".into();
+        processes[1].container_id = "0123456789ab".into();
+
+        assert_eq!(
+            groups(processes.clone(), AggregateBy::Cgroup),
+            [
+                ("-".into(), 1, 2.0),
+                ("/system.slice/nginx.service".into(), 1, 1.0)
+            ]
+        );
+        assert_eq!(
+            groups(processes.clone(), AggregateBy::Unit),
+            [("-".into(), 1, 2.0), ("nginx.service".into(), 1, 1.0)]
+        );
+        assert_eq!(
+            groups(processes, AggregateBy::Container),
+            [("docker:0123456789ab".into(), 1, 2.0), ("-".into(), 1, 1.0)]
+        );
+    }
+}
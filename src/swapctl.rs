@@ -0,0 +1,847 @@
+use std::ffi::CString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+
+use procfs::{Current, Meminfo};
+use thiserror::Error;
+
+use crate::swap_info::{InfoSwap, SizeUnits, get_swap_devices};
+
+const SWAP_FLAG_PREFER: libc::c_int = 0x8000;
+const SWAP_FLAG_PRIO_MASK: libc::c_int = 0x7fff;
+
+/// Highest priority `swapon` accepts, lower values are assigned by the kernel.
+pub const MAX_PRIORITY: i32 = SWAP_FLAG_PRIO_MASK;
+
+const FS_NOCOW_FL: libc::c_int = 0x0080_0000;
+
+// filesystems that can hold a swap file, btrfs only with nocow
+const EXT_MAGIC: i64 = 0xef53;
+const XFS_MAGIC: i64 = 0x5846_5342;
+const F2FS_MAGIC: i64 = 0xf2f5_2010;
+const BTRFS_MAGIC: i64 = 0x9123_683e;
+
+/// `mkswap` refuses areas smaller than ten pages, keep a sane floor.
+pub const MIN_SWAP_FILE_BYTES: u64 = 1024 * 1024;
+/// The swap header stores the index of the last page in 32 bits.
+const MAX_PAGES: u64 = 1 << 32;
+
+#[derive(Debug, Error)]
+pub enum SwapCtlError {
+    #[error("permission denied, managing swap requires root")]
+    PermissionDenied,
+    #[error("{0} is already in use or is not a swap area")]
+    Busy(String),
+    #[error("{path}: {source}")]
+    Io { path: String, source: io::Error },
+    #[error("{0} was turned off but could not be turned back on: {1}")]
+    LeftOff(String, Box<SwapCtlError>),
+    #[error("{0} already exists")]
+    Exists(String),
+    #[error("{0} is on a filesystem that cannot hold swap files")]
+    UnsupportedFilesystem(String),
+    #[error("{0} is on btrfs and nocow could not be set, swap files need it")]
+    NoCow(String),
+    #[error("not enough free space: {needed_mb} MB needed, {available_mb} MB available")]
+    NoSpace { needed_mb: u64, available_mb: u64 },
+    #[error("swap files must be at least 1 MB")]
+    TooSmall,
+    #[error("swap files can hold at most {0} GB")]
+    TooLarge(u64),
+}
+
+fn check(result: libc::c_int, path: &Path) -> Result<(), SwapCtlError> {
+    if result == 0 {
+        return Ok(());
+    }
+    let error = io::Error::last_os_error();
+    Err(match error.raw_os_error() {
+        Some(libc::EPERM) => SwapCtlError::PermissionDenied,
+        Some(libc::EBUSY) => SwapCtlError::Busy(path.display().to_string()),
+        _ => SwapCtlError::Io {
+            path: path.display().to_string(),
+            source: error,
+        },
+    })
+}
+
+fn c_path(path: &Path) -> Result<CString, SwapCtlError> {
+    CString::new(path.as_os_str().as_bytes()).map_err(|e| SwapCtlError::Io {
+        path: path.display().to_string(),
+        source: e.into(),
+    })
+}
+
+/// Enables a swap area, with the kernel picking the priority when `priority` is `None`
+/// or negative, the flags cannot express a negative priority.
+pub fn swap_on(path: &Path, priority: Option<i32>) -> Result<(), SwapCtlError> {
+    let path_c = c_path(path)?;
+    check(
+        unsafe { libc::swapon(path_c.as_ptr(), swap_flags(priority)) },
+        path,
+    )
+}
+
+fn swap_flags(priority: Option<i32>) -> libc::c_int {
+    match priority {
+        Some(p) if p >= 0 => SWAP_FLAG_PREFER | (p.min(MAX_PRIORITY) & SWAP_FLAG_PRIO_MASK),
+        _ => 0,
+    }
+}
+
+/// Disables a swap area. Blocks until every page in it has been read back.
+pub fn swap_off(path: &Path) -> Result<(), SwapCtlError> {
+    let path_c = c_path(path)?;
+    check(unsafe { libc::swapoff(path_c.as_ptr()) }, path)
+}
+
+/// The kernel cannot change the priority of an active area, so it is turned
+/// off and back on.
+pub fn set_priority(path: &Path, priority: i32) -> Result<(), SwapCtlError> {
+    reenable(path, priority, swap_off, swap_on)
+}
+
+/// `set_priority` with the syscalls passed in.
+fn reenable(
+    path: &Path,
+    priority: i32,
+    off: impl FnOnce(&Path) -> Result<(), SwapCtlError>,
+    on: impl FnOnce(&Path, Option<i32>) -> Result<(), SwapCtlError>,
+) -> Result<(), SwapCtlError> {
+    off(path)?;
+    on(path, Some(priority))
+        .map_err(|e| SwapCtlError::LeftOff(path.display().to_string(), Box::new(e)))
+}
+
+fn io_error(path: &Path, source: io::Error) -> SwapCtlError {
+    SwapCtlError::Io {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+/// Parses sizes such as `512M`, `2G` or `1048576`, binary multiples.
+pub fn parse_size(input: &str) -> Option<u64> {
+    let input = input.trim();
+    let (number, multiplier) = match input.chars().last()?.to_ascii_uppercase() {
+        'K' => (&input[..input.len() - 1], 1 << 10),
+        'M' => (&input[..input.len() - 1], 1 << 20),
+        'G' => (&input[..input.len() - 1], 1 << 30),
+        'T' => (&input[..input.len() - 1], 1 << 40),
+        _ => (input, 1),
+    };
+    number.trim().parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// Checks that a swap file of `size` bytes can be created at `path`: it must not
+/// exist yet, and its filesystem must support swap files and have the space.
+/// Returns whether the filesystem is btrfs.
+pub fn check_swap_file(path: &Path, size: u64) -> Result<bool, SwapCtlError> {
+    if size < MIN_SWAP_FILE_BYTES {
+        return Err(SwapCtlError::TooSmall);
+    }
+    let page_size = procfs::page_size();
+    if size / page_size > MAX_PAGES {
+        return Err(SwapCtlError::TooLarge((MAX_PAGES * page_size) >> 30));
+    }
+    if path.exists() {
+        return Err(SwapCtlError::Exists(path.display().to_string()));
+    }
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let parent_c = c_path(parent)?;
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(parent_c.as_ptr(), &mut stat) } != 0 {
+        return Err(io_error(parent, io::Error::last_os_error()));
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    let fs_type = stat.f_type as i64;
+    if ![EXT_MAGIC, XFS_MAGIC, F2FS_MAGIC, BTRFS_MAGIC].contains(&fs_type) {
+        return Err(SwapCtlError::UnsupportedFilesystem(
+            path.display().to_string(),
+        ));
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    let available = stat.f_bavail as u64 * stat.f_bsize as u64;
+    if available < size {
+        return Err(SwapCtlError::NoSpace {
+            needed_mb: size >> 20,
+            available_mb: available >> 20,
+        });
+    }
+    Ok(fs_type == BTRFS_MAGIC)
+}
+
+/// Sets nocow on an empty file and reads it back, btrfs ignores it otherwise.
+fn set_nocow(file: &File) -> bool {
+    let fd = file.as_raw_fd();
+    let mut flags: libc::c_int = 0;
+    unsafe {
+        if libc::ioctl(fd, libc::FS_IOC_GETFLAGS, &mut flags) != 0 {
+            return false;
+        }
+        flags |= FS_NOCOW_FL;
+        if libc::ioctl(fd, libc::FS_IOC_SETFLAGS, &flags) != 0
+            || libc::ioctl(fd, libc::FS_IOC_GETFLAGS, &mut flags) != 0
+        {
+            return false;
+        }
+    }
+    flags & FS_NOCOW_FL != 0
+}
+
+/// Allocates the whole file, swap files cannot have holes. Falls back to
+/// writing zeros where `fallocate` is not supported.
+fn allocate(file: &mut File, size: u64) -> io::Result<()> {
+    let length = libc::off_t::try_from(size).map_err(io::Error::other)?;
+    if unsafe { libc::fallocate(file.as_raw_fd(), 0, 0, length) } == 0 {
+        return Ok(());
+    }
+    let error = io::Error::last_os_error();
+    if error.raw_os_error() != Some(libc::EOPNOTSUPP) {
+        return Err(error);
+    }
+
+    let chunk = vec![0u8; 1 << 20];
+    let mut written = 0;
+    while written < size {
+        let length = chunk.len().min((size - written) as usize);
+        file.write_all(&chunk[..length])?;
+        written += length as u64;
+    }
+    Ok(())
+}
+
+/// Writes the version 1 swap header that `mkswap` writes: the page count at
+/// offset 1024, a random UUID and the `SWAPSPACE2` signature at the end of
+/// the first page.
+fn write_header(file: &mut File, size: u64) -> io::Result<()> {
+    let mut uuid = [0u8; 16];
+    File::open("/dev/urandom")
+        .and_then(|mut random| io::Read::read_exact(&mut random, &mut uuid))?;
+    uuid[6] = (uuid[6] & 0x0f) | 0x40;
+    uuid[8] = (uuid[8] & 0x3f) | 0x80;
+
+    let header = swap_header(procfs::page_size(), size, uuid)
+        .ok_or_else(|| io::Error::other("too many pages for a swap header"))?;
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(&header)?;
+    file.sync_all()
+}
+
+/// The first page of a swap area of `size` bytes, `None` when the index of
+/// its last page does not fit the 32-bit header field.
+fn swap_header(page_size: u64, size: u64, uuid: [u8; 16]) -> Option<Vec<u8>> {
+    let last_page = u32::try_from(size / page_size - 1).ok()?;
+    let mut header = vec![0u8; page_size as usize];
+    header[1024..1028].copy_from_slice(&1u32.to_ne_bytes());
+    header[1028..1032].copy_from_slice(&last_page.to_ne_bytes());
+    header[1036..1052].copy_from_slice(&uuid);
+    let signature = header.len() - 10;
+    header[signature..].copy_from_slice(b"SWAPSPACE2");
+    Some(header)
+}
+
+/// Creates a swap file readable only by root, formats it and turns it on.
+/// The file is removed again when any step fails.
+pub fn create_swap_file(path: &Path, size: u64, priority: Option<i32>) -> Result<(), SwapCtlError> {
+    let btrfs = check_swap_file(path, size)?;
+    // whole pages only
+    let size = size - size % procfs::page_size();
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .map_err(|e| io_error(path, e))?;
+
+    let result = (|| {
+        if btrfs && !set_nocow(&file) {
+            return Err(SwapCtlError::NoCow(path.display().to_string()));
+        }
+        allocate(&mut file, size).map_err(|e| io_error(path, e))?;
+        write_header(&mut file, size).map_err(|e| io_error(path, e))?;
+        drop(file);
+        swap_on(path, priority)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(path);
+    }
+    result
+}
+
+/// A swap entry of `/etc/fstab` that is not active.
+#[derive(Debug, Clone)]
+pub struct FstabSwap {
+    pub spec: String,
+    pub path: PathBuf,
+    pub priority: Option<i32>,
+}
+
+/// Resolves `UUID=`, `LABEL=`, `PARTUUID=` and `PARTLABEL=` through `/dev/disk`.
+fn resolve_spec(spec: &str) -> PathBuf {
+    let links = [
+        ("UUID=", "/dev/disk/by-uuid"),
+        ("LABEL=", "/dev/disk/by-label"),
+        ("PARTUUID=", "/dev/disk/by-partuuid"),
+        ("PARTLABEL=", "/dev/disk/by-partlabel"),
+    ];
+    for (prefix, dir) in links {
+        if let Some(id) = spec.strip_prefix(prefix) {
+            return Path::new(dir).join(id.trim_matches('"'));
+        }
+    }
+    PathBuf::from(spec)
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Swap entries of `/etc/fstab` that are not among the `active` devices.
+pub fn inactive_fstab_entries(active: &[InfoSwap]) -> Vec<FstabSwap> {
+    let Ok(fstab) = fs::read_to_string("/etc/fstab") else {
+        return Vec::new();
+    };
+
+    parse_fstab(&fstab)
+        .into_iter()
+        .filter(|entry| {
+            !active
+                .iter()
+                .any(|device| same_file(&entry.path, Path::new(&device.name)))
+        })
+        .collect()
+}
+
+/// The swap entries of an fstab file.
+fn parse_fstab(fstab: &str) -> Vec<FstabSwap> {
+    fstab
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 3 || fields[2] != "swap" {
+                return None;
+            }
+            let priority = fields.get(3).and_then(|options| {
+                options
+                    .split(',')
+                    .find_map(|option| option.strip_prefix("pri=")?.parse().ok())
+            });
+            Some(FstabSwap {
+                spec: fields[0].to_owned(),
+                path: resolve_spec(fields[0]),
+                priority,
+            })
+        })
+        .collect()
+}
+
+/// One row of the device manager, active areas first.
+#[derive(Debug, Clone)]
+pub struct DeviceEntry {
+    pub path: PathBuf,
+    pub label: String,
+    pub kind: String,
+    pub size_kb: u64,
+    pub used_kb: u64,
+    pub priority: Option<i32>,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceAction {
+    SwapOff,
+    SwapOn(Option<i32>),
+    SetPriority(i32),
+    Create { size: u64, priority: Option<i32> },
+}
+
+impl DeviceAction {
+    pub fn describe(self, path: &Path) -> String {
+        let path = path.display();
+        match self {
+            DeviceAction::SwapOff => format!("swapoff {path}"),
+            DeviceAction::SwapOn(None) => format!("swapon {path}"),
+            DeviceAction::SwapOn(Some(priority)) => format!("swapon -p {priority} {path}"),
+            DeviceAction::SetPriority(priority) => {
+                format!("set the priority of {path} to {priority}")
+            }
+            DeviceAction::Create { size, priority } => {
+                let mut description = format!("create a {} MB swap file at {path}", size >> 20);
+                if let Some(priority) = priority {
+                    description.push_str(&format!(" with priority {priority}"));
+                }
+                description
+            }
+        }
+    }
+
+    fn run(self, path: &Path) -> Result<(), SwapCtlError> {
+        match self {
+            DeviceAction::SwapOff => swap_off(path),
+            DeviceAction::SwapOn(priority) => swap_on(path, priority),
+            DeviceAction::SetPriority(priority) => set_priority(path, priority),
+            DeviceAction::Create { size, priority } => create_swap_file(path, size, priority),
+        }
+    }
+
+    /// Whether the pages in the area have to move back to RAM first.
+    pub fn empties_device(self) -> bool {
+        matches!(self, DeviceAction::SwapOff | DeviceAction::SetPriority(_))
+    }
+}
+
+/// The new swap file form, `field` indexes the input being typed into.
+#[derive(Debug)]
+pub struct NewSwapFile {
+    pub path: String,
+    pub size: String,
+    pub priority: String,
+    pub field: usize,
+}
+
+impl Default for NewSwapFile {
+    fn default() -> Self {
+        Self {
+            path: "/swapfile".into(),
+            size: "1G".into(),
+            priority: String::new(),
+            field: 0,
+        }
+    }
+}
+
+impl NewSwapFile {
+    pub const LABELS: [&str; 3] = ["path", "size", "priority"];
+
+    pub fn values(&self) -> [&str; 3] {
+        [&self.path, &self.size, &self.priority]
+    }
+
+    pub fn input(&mut self) -> &mut String {
+        match self.field {
+            0 => &mut self.path,
+            1 => &mut self.size,
+            _ => &mut self.priority,
+        }
+    }
+
+    /// Validates the form and checks the target filesystem.
+    pub fn action(&self) -> Result<(PathBuf, DeviceAction), String> {
+        let path = PathBuf::from(self.path.trim());
+        if !path.is_absolute() {
+            return Err("the path must be absolute".into());
+        }
+        let size = parse_size(&self.size)
+            .ok_or("the size must be a number with an optional K, M, G or T suffix")?;
+        let priority = match self.priority.trim() {
+            "" => None,
+            priority => match priority.parse() {
+                Ok(priority) if (0..=MAX_PRIORITY).contains(&priority) => Some(priority),
+                _ => return Err(format!("priority must be 0-{MAX_PRIORITY}")),
+            },
+        };
+        check_swap_file(&path, size).map_err(|e| e.to_string())?;
+        Ok((path, DeviceAction::Create { size, priority }))
+    }
+}
+
+/// State of the swap device manager: the selected row, a priority being typed,
+/// the new swap file form, the action waiting for confirmation and the one
+/// running in the background.
+#[derive(Debug, Default)]
+pub struct DeviceManager {
+    pub entries: Vec<DeviceEntry>,
+    pub selected: usize,
+    pub priority_input: Option<String>,
+    pub new_file: Option<NewSwapFile>,
+    pub confirm: Option<(PathBuf, DeviceAction)>,
+    pub running: Option<(String, JoinHandle<Result<(), SwapCtlError>>)>,
+    pub error: Option<String>,
+    pub mem_available_kb: u64,
+}
+
+impl DeviceManager {
+    pub fn open() -> io::Result<Self> {
+        let mut manager = Self::default();
+        manager.reload()?;
+        Ok(manager)
+    }
+
+    /// Rereads `/proc/swaps` and `/etc/fstab`.
+    pub fn reload(&mut self) -> io::Result<()> {
+        let active = get_swap_devices(SizeUnits::KB)?;
+        let inactive = inactive_fstab_entries(&active);
+        let previous = std::mem::take(&mut self.entries);
+
+        self.entries = active
+            .into_iter()
+            .map(|device| DeviceEntry {
+                path: PathBuf::from(&device.name),
+                label: device.name,
+                kind: device.kind,
+                size_kb: device.size_kb as u64,
+                used_kb: device.used_kb as u64,
+                priority: Some(device.priority as i32),
+                active: true,
+            })
+            .chain(inactive.into_iter().map(|entry| DeviceEntry {
+                label: entry.spec,
+                path: entry.path,
+                kind: "fstab".into(),
+                size_kb: 0,
+                used_kb: 0,
+                priority: entry.priority,
+                active: false,
+            }))
+            .collect();
+
+        // areas turned off here stay listed so they can be turned back on
+        for entry in previous {
+            if !self.entries.iter().any(|e| same_file(&e.path, &entry.path)) {
+                self.entries.push(DeviceEntry {
+                    size_kb: 0,
+                    used_kb: 0,
+                    active: false,
+                    ..entry
+                });
+            }
+        }
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+        self.mem_available_kb = Meminfo::current()
+            .ok()
+            .and_then(|meminfo| meminfo.mem_available)
+            .unwrap_or(0)
+            / 1024;
+        Ok(())
+    }
+
+    pub fn selected_entry(&self) -> Option<&DeviceEntry> {
+        self.entries.get(self.selected)
+    }
+
+    /// Turning the selected area off moves its pages to RAM, which can invoke
+    /// the OOM killer when they do not fit.
+    pub fn exceeds_available(&self) -> bool {
+        self.confirm
+            .as_ref()
+            .is_some_and(|(_, action)| action.empties_device())
+            && self
+                .selected_entry()
+                .is_some_and(|entry| entry.used_kb > self.mem_available_kb)
+    }
+
+    /// Asks to confirm `action` on the selected row.
+    pub fn confirm_selected(&mut self, action: DeviceAction) {
+        if let Some(entry) = self.selected_entry() {
+            self.confirm = Some((entry.path.clone(), action));
+            self.error = None;
+        }
+    }
+
+    /// Runs the confirmed action on a background thread, `swapoff` and
+    /// allocating a swap file can take a long time.
+    pub fn start(&mut self) {
+        let Some((path, action)) = self.confirm.take() else {
+            return;
+        };
+        let description = action.describe(&path);
+        self.error = None;
+        self.running = Some((description, thread::spawn(move || action.run(&path))));
+    }
+
+    /// Collects the result of a finished action, `None` while one is running.
+    pub fn finish(&mut self) -> Option<(String, Result<(), SwapCtlError>)> {
+        if !self.running.as_ref()?.1.is_finished() {
+            return None;
+        }
+        let (description, handle) = self.running.take()?;
+        let result = handle.join().unwrap_or_else(|_| {
+            Err(SwapCtlError::Io {
+                path: description.clone(),
+                source: io::Error::other("the swap operation panicked"),
+            })
+        });
+        Some((description, result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    use crate::swap_info::read_swap_devices;
+
+    const SWAPS: &str = "\
+Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority
+/swapfile                               file\t\t2097148\t\t524288\t\t-2
+/dev/zram0                              partition\t4194300\t\t0\t\t100
+/dev/dm-1                               partition\t8388604\t\t1024\t\t-3
+";
+
+    #[test]
+    fn reads_proc_swaps() {
+        let devices = read_swap_devices(SWAPS.as_bytes(), SizeUnits::KB).unwrap();
+        let rows: Vec<(&str, &str, f64, f64, isize)> = devices
+            .iter()
+            .map(|d| {
+                (
+                    d.name.as_str(),
+                    d.kind.as_str(),
+                    d.size_kb,
+                    d.used_kb,
+                    d.priority,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("/swapfile", "file", 2097148.0, 524288.0, -2),
+                ("/dev/zram0", "partition", 4194300.0, 0.0, 100),
+                ("/dev/dm-1", "partition", 8388604.0, 1024.0, -3),
+            ]
+        );
+
+        let devices = read_swap_devices(SWAPS.as_bytes(), SizeUnits::MB).unwrap();
+        assert_eq!(devices[0].used_kb, 512.0);
+    }
+
+    #[test]
+    fn reads_empty_proc_swaps() {
+        let header = "Filename\tType\tSize\tUsed\tPriority\n";
+        assert!(
+            read_swap_devices(header.as_bytes(), SizeUnits::KB)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn parses_fstab_swap_entries() {
+        let fstab = "\
+# /etc/fstab: static file system information.
+UUID=0a1b2c3d-4e5f-6789-abcd-ef0123456789 /     ext4 errors=remount-ro 0 1
+UUID=9f8e7d6c-5b4a-3210-fedc-ba9876543210 none  swap sw,pri=10        0 0
+/swapfile                                 none  swap defaults         0 0
+  LABEL=\"backup\"                          none  swap pri=-1           0 0
+#/dev/sdb2                                none  swap sw               0 0
+PARTUUID=1234-02                          none  swap sw,pri=x
+/dev/sdc1                                 /data xfs  defaults         0 2
+tmpfs                                     /tmp  tmpfs
+/dev/sdd
+";
+        let entries: Vec<(String, PathBuf, Option<i32>)> = parse_fstab(fstab)
+            .into_iter()
+            .map(|e| (e.spec, e.path, e.priority))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                (
+                    "UUID=9f8e7d6c-5b4a-3210-fedc-ba9876543210".to_owned(),
+                    PathBuf::from("/dev/disk/by-uuid/9f8e7d6c-5b4a-3210-fedc-ba9876543210"),
+                    Some(10),
+                ),
+                ("/swapfile".to_owned(), PathBuf::from("/swapfile"), None),
+                (
+                    "LABEL=\"backup\"".to_owned(),
+                    PathBuf::from("/dev/disk/by-label/backup"),
+                    Some(-1),
+                ),
+                (
+                    "PARTUUID=1234-02".to_owned(),
+                    PathBuf::from("/dev/disk/by-partuuid/1234-02"),
+                    None,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_fstab_specs() {
+        assert_eq!(
+            resolve_spec("PARTLABEL=\"swap\""),
+            PathBuf::from("/dev/disk/by-partlabel/swap")
+        );
+        assert_eq!(resolve_spec("/dev/sda2"), PathBuf::from("/dev/sda2"));
+    }
+
+    #[test]
+    fn swap_flags_encode_the_priority() {
+        assert_eq!(swap_flags(None), 0);
+        assert_eq!(swap_flags(Some(0)), SWAP_FLAG_PREFER);
+        assert_eq!(swap_flags(Some(5)), SWAP_FLAG_PREFER | 5);
+        assert_eq!(swap_flags(Some(-1)), 0);
+        assert_eq!(swap_flags(Some(-4)), 0);
+        assert_eq!(swap_flags(Some(i32::MAX)), SWAP_FLAG_PREFER | MAX_PRIORITY);
+    }
+
+    #[test]
+    fn set_priority_turns_the_area_off_then_on() {
+        let calls = RefCell::new(Vec::new());
+        let result = reenable(
+            Path::new("/swapfile"),
+            7,
+            |path| {
+                calls.borrow_mut().push(format!("off {}", path.display()));
+                Ok(())
+            },
+            |path, priority| {
+                calls
+                    .borrow_mut()
+                    .push(format!("on {} {priority:?}", path.display()));
+                Ok(())
+            },
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            calls.into_inner(),
+            ["off /swapfile", "on /swapfile Some(7)"]
+        );
+    }
+
+    #[test]
+    fn set_priority_keeps_the_area_when_swapoff_fails() {
+        let mut turned_on = false;
+        let result = reenable(
+            Path::new("/swapfile"),
+            7,
+            |path| Err(SwapCtlError::Busy(path.display().to_string())),
+            |_, _| {
+                turned_on = true;
+                Ok(())
+            },
+        );
+        assert!(matches!(result, Err(SwapCtlError::Busy(_))));
+        assert!(!turned_on);
+    }
+
+    #[test]
+    fn set_priority_reports_an_area_left_off() {
+        let result = reenable(
+            Path::new("/swapfile"),
+            7,
+            |_| Ok(()),
+            |_, _| Err(SwapCtlError::PermissionDenied),
+        );
+        match result {
+            Err(SwapCtlError::LeftOff(path, source)) => {
+                assert_eq!(path, "/swapfile");
+                assert!(matches!(*source, SwapCtlError::PermissionDenied));
+            }
+            other => panic!("expected LeftOff, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_sizes_with_binary_suffixes() {
+        let cases = [
+            ("1048576", Some(1 << 20)),
+            ("64K", Some(64 << 10)),
+            ("512M", Some(512 << 20)),
+            ("512m", Some(512 << 20)),
+            ("2G", Some(2 << 30)),
+            ("1T", Some(1 << 40)),
+            (" 4 G ", Some(4 << 30)),
+            ("0", Some(0)),
+            ("", None),
+            ("G", None),
+            ("1.5G", None),
+            ("-1G", None),
+            ("10MB", None),
+            ("ten", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_size(input), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn parse_size_rejects_overflow() {
+        assert_eq!(parse_size("18446744073709551615"), Some(u64::MAX));
+        assert_eq!(parse_size("18446744073709551616"), None);
+        assert_eq!(parse_size("16777215T"), Some(16_777_215 << 40));
+        assert_eq!(parse_size("16777216T"), None);
+        assert_eq!(parse_size("17179869184G"), None);
+    }
+
+    fn field(header: &[u8], offset: usize) -> u32 {
+        u32::from_ne_bytes(header[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn swap_header_matches_mkswap_v1() {
+        let uuid: [u8; 16] = std::array::from_fn(|i| i as u8 + 1);
+        let header = swap_header(4096, 4 << 20, uuid).unwrap();
+
+        assert_eq!(header.len(), 4096);
+        assert_eq!(&header[4086..], b"SWAPSPACE2");
+        assert_eq!(field(&header, 1024), 1, "version");
+        assert_eq!(field(&header, 1028), 1023, "last_page");
+        assert_eq!(field(&header, 1032), 0, "nr_badpages");
+        assert_eq!(header[1036..1052], uuid);
+        // boot block, volume label and padding stay zeroed
+        assert!(header[..1024].iter().all(|&b| b == 0));
+        assert!(header[1052..4086].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn swap_header_follows_the_page_size() {
+        let header = swap_header(65536, (64 << 20) + 4096, [0; 16]).unwrap();
+        assert_eq!(header.len(), 65536);
+        assert_eq!(&header[65526..], b"SWAPSPACE2");
+        assert_eq!(field(&header, 1028), 1023, "partial pages are not counted");
+    }
+
+    #[test]
+    fn swap_header_rejects_more_than_2_32_pages() {
+        let header = swap_header(4096, MAX_PAGES * 4096, [0; 16]).unwrap();
+        assert_eq!(field(&header, 1028), u32::MAX);
+        assert_eq!(swap_header(4096, (MAX_PAGES + 1) * 4096, [0; 16]), None);
+    }
+
+    /// Runs the real syscalls on a swap file in `SWAPTOP_TEST_DIR`, which must be
+    /// on ext4, xfs, f2fs or btrfs. Needs root:
+    /// `SWAPTOP_TEST_DIR=/var/tmp cargo test -- --ignored`
+    #[test]
+    #[ignore = "needs root and SWAPTOP_TEST_DIR"]
+    fn manages_a_real_swap_file() {
+        let dir = PathBuf::from(std::env::var_os("SWAPTOP_TEST_DIR").expect("SWAPTOP_TEST_DIR"));
+        let path = dir.join(format!("swaptop-test-{}", std::process::id()));
+        let priority_of = |path: &Path| {
+            get_swap_devices(SizeUnits::KB)
+                .unwrap()
+                .into_iter()
+                .find(|d| same_file(Path::new(&d.name), path))
+                .map(|d| d.priority)
+        };
+
+        create_swap_file(&path, 4 << 20, Some(3)).unwrap();
+        assert_eq!(priority_of(&path), Some(3));
+        set_priority(&path, 9).unwrap();
+        assert_eq!(priority_of(&path), Some(9));
+        swap_off(&path).unwrap();
+        assert_eq!(priority_of(&path), None);
+        fs::remove_file(&path).unwrap();
+    }
+}
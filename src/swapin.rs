@@ -0,0 +1,289 @@
+use std::fs::File;
+use std::io::Write;
+use std::ops::Range;
+use std::os::unix::fs::FileExt;
+
+use color_eyre::eyre::{Result, eyre};
+use procfs::process::{PageInfo, Process};
+use procfs::{Current, Meminfo};
+
+use crate::mappings::SwapBreakdown;
+use crate::swap_info::SwapDataError;
+
+/// Bytes faulted in per `step`, small enough to keep the TUI responsive.
+pub const STEP_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Pagemap entries read at once while planning.
+const PAGEMAP_CHUNK: usize = 64 * 1024;
+
+/// Faults the swapped pages of a process back into RAM by reading them through
+/// `/proc/PID/mem`. The pages are located with `/proc/PID/pagemap`, so resident
+/// pages and untouched file mappings are never read.
+#[derive(Debug)]
+pub struct SwapIn {
+    pub pid: u32,
+    pub name: String,
+    /// `Swap` total of `/proc/PID/smaps` in bytes.
+    pub estimate: u64,
+    pub mem_available: u64,
+    pub total_pages: u64,
+    pub done_pages: u64,
+    /// Pages that were unmapped before they could be read.
+    pub skipped_pages: u64,
+    pub started: bool,
+    page_size: u64,
+    runs: Vec<Range<u64>>,
+    next_run: usize,
+    mem: Option<File>,
+}
+
+impl SwapIn {
+    /// Reads the plan without touching the process, this is the dry run.
+    pub fn plan(pid: u32) -> Result<Self, SwapDataError> {
+        let breakdown = SwapBreakdown::read(pid)?;
+        let process = Process::new(pid as i32)?;
+        let page_size = procfs::page_size();
+        let mut pagemap = process.pagemap()?;
+
+        // coalesce neighbouring swapped pages into runs to read them in one go
+        let mut runs: Vec<Range<u64>> = Vec::new();
+        for mapping in &breakdown.mappings {
+            let last = (mapping.end / page_size) as usize;
+            let mut first = (mapping.start / page_size) as usize;
+            // large sparse mappings are scanned in chunks
+            while first < last {
+                let end = (first + PAGEMAP_CHUNK).min(last);
+                for (index, info) in pagemap.get_range_info(first..end)?.into_iter().enumerate() {
+                    if !matches!(info, PageInfo::SwapPage(_)) {
+                        continue;
+                    }
+                    let address = (first + index) as u64 * page_size;
+                    match runs.last_mut() {
+                        Some(run) if run.end == address => run.end += page_size,
+                        _ => runs.push(address..address + page_size),
+                    }
+                }
+                first = end;
+            }
+        }
+
+        Ok(Self {
+            pid,
+            name: breakdown.name,
+            estimate: breakdown.mappings.iter().map(|m| m.swap_kb * 1024).sum(),
+            mem_available: Meminfo::current()?.mem_available.unwrap_or(0),
+            total_pages: runs.iter().map(|r| (r.end - r.start) / page_size).sum(),
+            done_pages: 0,
+            skipped_pages: 0,
+            started: false,
+            page_size,
+            runs,
+            next_run: 0,
+            mem: None,
+        })
+    }
+
+    /// Whether MemAvailable covers the swapped pages, swapping them in would
+    /// otherwise only push other pages out.
+    pub fn fits(&self) -> bool {
+        self.total_bytes() < self.mem_available
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.total_pages * self.page_size
+    }
+
+    pub fn done_bytes(&self) -> u64 {
+        self.done_pages * self.page_size
+    }
+
+    pub fn skipped_bytes(&self) -> u64 {
+        self.skipped_pages * self.page_size
+    }
+
+    pub fn progress(&self) -> f64 {
+        if self.total_pages == 0 {
+            1.0
+        } else {
+            (self.done_pages + self.skipped_pages) as f64 / self.total_pages as f64
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.next_run >= self.runs.len()
+    }
+
+    /// Reads up to `budget` bytes of swapped pages. Runs that were unmapped
+    /// since the plan was made are skipped, a process that exited is an error.
+    pub fn step(&mut self, budget: u64) -> Result<(), SwapDataError> {
+        self.started = true;
+        if self.mem.is_none() {
+            self.mem = Some(Process::new(self.pid as i32)?.mem()?);
+        }
+        let Some(mem) = &self.mem else {
+            return Ok(());
+        };
+
+        let mut buffer = vec![0u8; budget as usize];
+        let mut spent = 0;
+        while spent < budget
+            && let Some(run) = self.runs.get_mut(self.next_run)
+        {
+            let length = (run.end - run.start).min(buffer.len() as u64);
+            let read = match mem.read_at(&mut buffer[..length as usize], run.start) {
+                Err(e) if e.raw_os_error() == Some(libc::ESRCH) => {
+                    return Err(SwapDataError::ProcessExited(self.pid));
+                }
+                result => result.unwrap_or(0) as u64,
+            };
+            // a short read stops at a page that is gone, the rest of the run is
+            // retried from there and only dropped once a read fails or returns nothing,
+            // which is also what reading the memory of an exited process does
+            let advanced = if read == 0 {
+                if !is_running(self.pid) {
+                    return Err(SwapDataError::ProcessExited(self.pid));
+                }
+                let remaining = run.end - run.start;
+                self.skipped_pages += remaining / self.page_size;
+                remaining
+            } else {
+                self.done_pages += read / self.page_size;
+                read
+            };
+            run.start += advanced;
+            spent += advanced;
+            if run.start >= run.end {
+                self.next_run += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether the process still exists and was not reaped yet, a zombie has no memory left to read.
+fn is_running(pid: u32) -> bool {
+    Process::new(pid as i32)
+        .and_then(|process| process.stat())
+        .is_ok_and(|stat| !matches!(stat.state, 'Z' | 'X'))
+}
+
+fn mb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+/// `swaptop swap-in`: prints the plan, then swaps the process in unless this is a dry run.
+pub fn run(pid: u32, dry_run: bool) -> Result<()> {
+    let mut swap_in = SwapIn::plan(pid)?;
+    println!("process:       {} ({})", swap_in.name, pid);
+    println!("swap (smaps):  {:.1} MB", mb(swap_in.estimate));
+    println!(
+        "swapped pages: {} ({:.1} MB)",
+        swap_in.total_pages,
+        mb(swap_in.total_bytes())
+    );
+    println!("MemAvailable:  {:.1} MB", mb(swap_in.mem_available));
+
+    if !swap_in.fits() {
+        return Err(eyre!(
+            "not enough available memory to swap in {:.1} MB",
+            mb(swap_in.total_bytes())
+        ));
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    let mut stderr = std::io::stderr();
+    while !swap_in.is_done() {
+        if let Err(e) = swap_in.step(STEP_BYTES) {
+            writeln!(stderr)?;
+            return Err(eyre!(
+                "{e} after swapping in {:.1} MB",
+                mb(swap_in.done_bytes())
+            ));
+        }
+        write!(stderr, "\rswapped in {:5.1}%", swap_in.progress() * 100.0)?;
+    }
+    writeln!(stderr)?;
+    println!(
+        "swapped in {:.1} MB, {:.1} MB no longer mapped",
+        mb(swap_in.done_bytes()),
+        mb(swap_in.skipped_bytes())
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Child, Command};
+
+    /// A plan over the top pages of the stack of a fresh process, one run per
+    /// page. The stack is mapped from `exec` on, unlike the libraries.
+    fn plan_stack(child: &Child, pages: u64) -> SwapIn {
+        let page_size = procfs::page_size();
+        let stack = Process::new(child.id() as i32)
+            .unwrap()
+            .maps()
+            .unwrap()
+            .into_iter()
+            .find(|map| map.pathname == procfs::process::MMapPath::Stack)
+            .unwrap();
+        let runs: Vec<Range<u64>> = (1..=pages)
+            .rev()
+            .map(|page| stack.address.1 - page * page_size)
+            .map(|address| address..address + page_size)
+            .collect();
+        SwapIn {
+            pid: child.id(),
+            name: "sleep".into(),
+            estimate: 0,
+            mem_available: 0,
+            total_pages: pages,
+            done_pages: 0,
+            skipped_pages: 0,
+            started: false,
+            page_size,
+            runs,
+            next_run: 0,
+            mem: None,
+        }
+    }
+
+    #[test]
+    fn stops_when_the_process_exits() {
+        let mut child = Command::new("sleep").arg("60").spawn().unwrap();
+        let mut swap_in = plan_stack(&child, 2);
+        let page_size = swap_in.page_size;
+
+        swap_in.step(page_size).unwrap();
+        assert_eq!((swap_in.done_pages, swap_in.skipped_pages), (1, 0));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        let result = swap_in.step(page_size);
+        assert!(
+            matches!(result, Err(SwapDataError::ProcessExited(pid)) if pid == child.id()),
+            "{result:?}"
+        );
+        assert_eq!((swap_in.done_pages, swap_in.skipped_pages), (1, 0));
+    }
+
+    #[test]
+    fn skips_pages_that_are_no_longer_mapped() {
+        let mut child = Command::new("sleep").arg("60").spawn().unwrap();
+        let mut swap_in = plan_stack(&child, 2);
+        // far above any mapping of a 64-bit process
+        let gone = 0x10_0000_0000;
+        swap_in.runs.insert(1, gone..gone + 3 * swap_in.page_size);
+        swap_in.total_pages += 3;
+
+        while !swap_in.is_done() {
+            swap_in.step(STEP_BYTES).unwrap();
+        }
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert_eq!((swap_in.done_pages, swap_in.skipped_pages), (2, 3));
+        assert_eq!(swap_in.progress(), 1.0);
+    }
+}
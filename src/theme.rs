@@ -1,6 +1,8 @@
 use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ThemeType {
     #[default]
     Default,
@@ -17,6 +19,8 @@ pub struct Theme {
     pub border: Color,
     pub background: Color,
     pub scrollbar: Color,
+    pub warning: Color,
+    pub critical: Color,
 }
 
 impl Theme {
@@ -38,6 +42,8 @@ impl Theme {
             border: Color::Rgb(80, 80, 120),
             background: Color::Rgb(20, 20, 30),
             scrollbar: Color::Rgb(100, 100, 140),
+            warning: Color::Rgb(255, 200, 80),
+            critical: Color::Rgb(255, 90, 90),
         }
     }
 
@@ -49,6 +55,8 @@ impl Theme {
             border: Color::Rgb(88, 110, 117),     // Base01
             background: Color::Rgb(0, 43, 54),    // Base03
             scrollbar: Color::Rgb(101, 123, 131), // Base00
+            warning: Color::Rgb(181, 137, 0),     // Yellow
+            critical: Color::Rgb(220, 50, 47),    // Red
         }
     }
 
@@ -60,6 +68,8 @@ impl Theme {
             border: Color::Rgb(117, 113, 94),     // Gray
             background: Color::Rgb(39, 40, 34),   // Dark gray
             scrollbar: Color::Rgb(105, 105, 105),
+            warning: Color::Rgb(253, 151, 31), // Orange
+            critical: Color::Rgb(255, 60, 60), // Red
         }
     }
 
@@ -71,6 +81,8 @@ impl Theme {
             border: Color::Rgb(98, 114, 164),     // Blue-gray
             background: Color::Rgb(40, 42, 54),   // Dark purple
             scrollbar: Color::Rgb(68, 71, 90),
+            warning: Color::Rgb(241, 250, 140), // Yellow
+            critical: Color::Rgb(255, 85, 85),  // Red
         }
     }
 
@@ -82,6 +94,8 @@ impl Theme {
             border: Color::Rgb(76, 86, 106),      // PolarNight2
             background: Color::Rgb(46, 52, 64),   // PolarNight0
             scrollbar: Color::Rgb(67, 76, 94),
+            warning: Color::Rgb(235, 203, 139), // Aurora yellow
+            critical: Color::Rgb(191, 97, 106), // Aurora red
         }
     }
 }
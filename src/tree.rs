@@ -0,0 +1,280 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::swap_info::{ProcessSwapInfo, SortColumn, sort_processes};
+
+/// One line of the tree view. `process.swap_size` is the swap of the process
+/// itself, `total` includes every descendant.
+#[derive(Debug, Clone)]
+pub struct TreeRow {
+    pub process: ProcessSwapInfo,
+    pub depth: usize,
+    pub total: f64,
+    pub has_children: bool,
+    pub collapsed: bool,
+}
+
+#[derive(Debug)]
+struct Node {
+    process: ProcessSwapInfo,
+    total: f64,
+    children: Vec<u32>,
+}
+
+/// Swapping processes nested under their parents. Ancestors that do not use
+/// swap themselves are included so every process sits at its real depth.
+#[derive(Debug, Default)]
+pub struct ProcessTree {
+    nodes: HashMap<u32, Node>,
+    roots: Vec<u32>,
+}
+
+/// PPID and name of a process from `/proc/PID/stat`.
+fn read_parent(pid: u32) -> Option<(u32, String)> {
+    let stat = procfs::process::Process::new(pid as i32)
+        .and_then(|process| process.stat())
+        .ok()?;
+    Some((stat.ppid as u32, stat.comm))
+}
+
+impl ProcessTree {
+    pub fn build(processes: &[ProcessSwapInfo]) -> Self {
+        let mut nodes: HashMap<u32, Node> = HashMap::new();
+
+        for process in processes {
+            nodes.insert(
+                process.pid,
+                Node {
+                    process: process.clone(),
+                    total: 0.0,
+                    children: Vec::new(),
+                },
+            );
+        }
+
+        // pull in the missing ancestors of every swapping process, only those
+        // are read from /proc, the listed processes carry their own PPID
+        for process in processes {
+            let mut pid = process.ppid;
+            let mut name = process.parent.clone();
+            while pid != 0 && !nodes.contains_key(&pid) {
+                // an ancestor that exited in the meantime becomes a root
+                let (ppid, comm) = read_parent(pid).unwrap_or((0, name));
+                nodes.insert(
+                    pid,
+                    Node {
+                        process: ProcessSwapInfo {
+                            pid,
+                            name: comm,
+                            swap_size: 0.0,
+                            user: String::new(),
+                            uid: None,
+                            ppid,
+                            parent: String::new(),
+                            cgroup: String::new(),
+                            systemd_unit: String::new(),
+                            container_id: String::new(),
+                            container_runtime: String::new(),
+                        },
+                        total: 0.0,
+                        children: Vec::new(),
+                    },
+                );
+                pid = ppid;
+                name = String::new();
+            }
+        }
+
+        let mut roots = Vec::new();
+        let pids: Vec<u32> = nodes.keys().copied().collect();
+        for pid in pids {
+            let ppid = nodes[&pid].process.ppid;
+            match nodes.get_mut(&ppid) {
+                Some(parent) if ppid != pid => parent.children.push(pid),
+                _ => roots.push(pid),
+            }
+        }
+
+        let mut tree = Self { nodes, roots };
+        tree.sum();
+        tree
+    }
+
+    /// Sums the subtree totals bottom up. PPIDs read at different times can form
+    /// a cycle that no root reaches, it is broken up by making one of its members
+    /// a root so every process is still listed.
+    fn sum(&mut self) {
+        let mut visited: HashSet<u32> = HashSet::new();
+        let mut next_root = 0;
+        loop {
+            while next_root < self.roots.len() {
+                self.sum_subtree(self.roots[next_root], &mut visited);
+                next_root += 1;
+            }
+            let Some(&orphan) = self.nodes.keys().find(|pid| !visited.contains(pid)) else {
+                break;
+            };
+            let ppid = self.nodes[&orphan].process.ppid;
+            if let Some(parent) = self.nodes.get_mut(&ppid) {
+                parent.children.retain(|&child| child != orphan);
+            }
+            self.roots.push(orphan);
+        }
+    }
+
+    fn sum_subtree(&mut self, root: u32, visited: &mut HashSet<u32>) {
+        // post-order without recursion: a node is summed once its children were
+        let mut stack = vec![(root, false)];
+        while let Some((pid, children_done)) = stack.pop() {
+            if children_done {
+                let node = &self.nodes[&pid];
+                let total = node.process.swap_size
+                    + node
+                        .children
+                        .iter()
+                        .map(|child| self.nodes[child].total)
+                        .sum::<f64>();
+                if let Some(node) = self.nodes.get_mut(&pid) {
+                    node.total = total;
+                }
+            } else if visited.insert(pid) {
+                stack.push((pid, true));
+                stack.extend(
+                    self.nodes[&pid]
+                        .children
+                        .iter()
+                        .map(|&child| (child, false)),
+                );
+            }
+        }
+    }
+
+    /// Siblings in the order of the list, sizes compare by subtree total.
+    fn ordered(&self, pids: &[u32], column: SortColumn, descending: bool) -> Vec<u32> {
+        let mut siblings: Vec<ProcessSwapInfo> = pids
+            .iter()
+            .map(|pid| {
+                let node = &self.nodes[pid];
+                ProcessSwapInfo {
+                    swap_size: node.total,
+                    ..node.process.clone()
+                }
+            })
+            .collect();
+        sort_processes(&mut siblings, column, descending);
+        siblings.into_iter().map(|p| p.pid).collect()
+    }
+
+    /// Flattens the tree in display order, skipping the descendants of collapsed nodes.
+    pub fn rows(
+        &self,
+        collapsed: &HashSet<u32>,
+        column: SortColumn,
+        descending: bool,
+    ) -> Vec<TreeRow> {
+        let mut rows = Vec::new();
+        let mut stack: Vec<(u32, usize)> = self
+            .ordered(&self.roots, column, descending)
+            .into_iter()
+            .rev()
+            .map(|pid| (pid, 0))
+            .collect();
+
+        while let Some((pid, depth)) = stack.pop() {
+            let node = &self.nodes[&pid];
+            let is_collapsed = collapsed.contains(&pid);
+            rows.push(TreeRow {
+                process: node.process.clone(),
+                depth,
+                total: node.total,
+                has_children: !node.children.is_empty(),
+                collapsed: is_collapsed,
+            });
+            if !is_collapsed {
+                let children = self.ordered(&node.children, column, descending);
+                stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+            }
+        }
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // above the largest PID Linux hands out, so no ancestor is found in /proc
+    const BASE: u32 = 5_000_000;
+
+    fn process(pid: u32, ppid: u32, parent: &str, swap_size: f64) -> ProcessSwapInfo {
+        ProcessSwapInfo {
+            pid: BASE + pid,
+            name: format!("p{pid}"),
+            swap_size,
+            user: String::new(),
+            uid: None,
+            ppid: if ppid == 0 { 0 } else { BASE + ppid },
+            parent: parent.to_owned(),
+            cgroup: String::new(),
+            systemd_unit: String::new(),
+            container_id: String::new(),
+            container_runtime: String::new(),
+        }
+    }
+
+    fn rows(processes: &[ProcessSwapInfo]) -> Vec<(String, usize, f64)> {
+        ProcessTree::build(processes)
+            .rows(&HashSet::new(), SortColumn::Swap, true)
+            .into_iter()
+            .map(|row| (row.process.name, row.depth, row.total))
+            .collect()
+    }
+
+    #[test]
+    fn nests_processes_and_sums_subtrees() {
+        let processes = [
+            process(1, 0, "", 1.0),
+            process(2, 1, "p1", 2.0),
+            process(3, 2, "p2", 4.0),
+            process(4, 1, "p1", 8.0),
+        ];
+        assert_eq!(
+            rows(&processes),
+            [
+                ("p1".into(), 0, 15.0),
+                ("p4".into(), 1, 8.0),
+                ("p2".into(), 1, 6.0),
+                ("p3".into(), 2, 4.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn adds_missing_parents_by_name() {
+        let processes = [process(2, 1, "init", 2.0), process(3, 1, "init", 4.0)];
+        assert_eq!(
+            rows(&processes),
+            [
+                ("init".into(), 0, 6.0),
+                ("p3".into(), 1, 4.0),
+                ("p2".into(), 1, 2.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn breaks_ppid_cycles() {
+        let processes = [
+            process(1, 3, "p3", 1.0),
+            process(2, 1, "p1", 2.0),
+            process(3, 2, "p2", 4.0),
+            process(4, 4, "p4", 8.0),
+        ];
+        let rows = rows(&processes);
+        assert_eq!(rows.len(), 4);
+        assert!(rows.contains(&("p4".into(), 0, 8.0)));
+        let cycle_root = rows
+            .iter()
+            .find(|(name, depth, _)| name != "p4" && *depth == 0);
+        assert_eq!(cycle_root.map(|(_, _, total)| *total), Some(7.0));
+    }
+}
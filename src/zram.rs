@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Compression statistics of an initialized zram device, sizes in bytes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ZramDevice {
+    pub name: String,
+    pub algorithm: String,
+    pub disk_size: u64,
+    pub orig_data_size: u64,
+    pub compr_data_size: u64,
+    pub mem_used_total: u64,
+    pub same_pages: u64,
+    pub huge_pages: u64,
+}
+
+impl ZramDevice {
+    /// Original size divided by the memory actually used to hold it.
+    pub fn compression_ratio(&self) -> f64 {
+        if self.mem_used_total == 0 {
+            0.0
+        } else {
+            self.orig_data_size as f64 / self.mem_used_total as f64
+        }
+    }
+}
+
+fn read_value(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_owned())
+}
+
+/// `comp_algorithm` lists every available algorithm with the active one in brackets.
+fn active_algorithm(algorithms: &str) -> String {
+    algorithms
+        .split_whitespace()
+        .find_map(|a| a.strip_prefix('[')?.strip_suffix(']'))
+        .unwrap_or(algorithms)
+        .to_owned()
+}
+
+fn read_device(dir: &Path) -> Option<ZramDevice> {
+    let disk_size = read_value(&dir.join("disksize"))?.parse().ok()?;
+    if disk_size == 0 {
+        return None;
+    }
+
+    // orig_data_size compr_data_size mem_used_total mem_limit mem_used_max
+    // same_pages pages_compacted huge_pages [huge_pages_since]
+    let mm_stat: Vec<u64> = read_value(&dir.join("mm_stat"))?
+        .split_whitespace()
+        .map(|field| field.parse().unwrap_or(0))
+        .collect();
+    let field = |index: usize| mm_stat.get(index).copied().unwrap_or(0);
+
+    Some(ZramDevice {
+        name: format!("/dev/{}", dir.file_name()?.to_string_lossy()),
+        algorithm: read_value(&dir.join("comp_algorithm"))
+            .map(|a| active_algorithm(&a))
+            .unwrap_or_else(|| "unknown".into()),
+        disk_size,
+        orig_data_size: field(0),
+        compr_data_size: field(1),
+        mem_used_total: field(2),
+        same_pages: field(5),
+        huge_pages: field(7),
+    })
+}
+
+/// Reads every initialized zram device from `/sys/block`.
+pub fn get_zram_devices() -> Vec<ZramDevice> {
+    let Ok(entries) = fs::read_dir("/sys/block") else {
+        return Vec::new();
+    };
+
+    let mut devices: Vec<ZramDevice> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("zram"))
+        .filter_map(|entry| read_device(&entry.path()))
+        .collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// A fake `/sys/block/zramN` directory, removed again when dropped.
+    struct SysDir(PathBuf);
+
+    impl SysDir {
+        fn new(test: &str, files: &[(&str, &str)]) -> Self {
+            let dir = std::env::temp_dir()
+                .join(format!("swaptop-{}-{test}", std::process::id()))
+                .join("zram0");
+            fs::create_dir_all(&dir).unwrap();
+            for (name, content) in files {
+                fs::write(dir.join(name), content).unwrap();
+            }
+            Self(dir)
+        }
+    }
+
+    impl Drop for SysDir {
+        fn drop(&mut self) {
+            if let Some(parent) = self.0.parent() {
+                let _ = fs::remove_dir_all(parent);
+            }
+        }
+    }
+
+    #[test]
+    fn picks_the_bracketed_algorithm() {
+        assert_eq!(active_algorithm("lzo [lzo-rle] lz4 zstd"), "lzo-rle");
+        assert_eq!(active_algorithm("[zstd]"), "zstd");
+        // older kernels only print the active one
+        assert_eq!(active_algorithm("lz4"), "lz4");
+    }
+
+    #[test]
+    fn reads_mm_stat_fields() {
+        let dir = SysDir::new(
+            "zram-mm-stat",
+            &[
+                ("disksize", "4294967296\n"),
+                ("comp_algorithm", "lzo [zstd]\n"),
+                (
+                    "mm_stat",
+                    "  8192000  2048000  2457600        0  2457600      120        0       15        3\n",
+                ),
+            ],
+        );
+        let device = read_device(&dir.0).unwrap();
+        assert_eq!(device.name, "/dev/zram0");
+        assert_eq!(device.algorithm, "zstd");
+        assert_eq!(device.disk_size, 4 << 30);
+        assert_eq!(device.orig_data_size, 8192000);
+        assert_eq!(device.compr_data_size, 2048000);
+        assert_eq!(device.mem_used_total, 2457600);
+        assert_eq!(device.same_pages, 120);
+        assert_eq!(device.huge_pages, 15);
+        assert!((device.compression_ratio() - 3.333).abs() < 0.001);
+    }
+
+    #[test]
+    fn short_mm_stat_and_missing_algorithm_default() {
+        let dir = SysDir::new(
+            "zram-short",
+            &[("disksize", "1048576"), ("mm_stat", "4096 1024 2048")],
+        );
+        let device = read_device(&dir.0).unwrap();
+        assert_eq!(device.algorithm, "unknown");
+        assert_eq!(device.mem_used_total, 2048);
+        assert_eq!((device.same_pages, device.huge_pages), (0, 0));
+    }
+
+    #[test]
+    fn skips_uninitialized_devices() {
+        let dir = SysDir::new("zram-reset", &[("disksize", "0"), ("mm_stat", "0 0 0")]);
+        assert!(read_device(&dir.0).is_none());
+        assert_eq!(ZramDevice::default().compression_ratio(), 0.0);
+    }
+}
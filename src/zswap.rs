@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const PARAMETERS: &str = "/sys/module/zswap/parameters";
+const DEBUGFS: &str = "/sys/kernel/debug/zswap";
+
+/// State of the zswap compressed cache in front of the swap devices. Sizes are
+/// in bytes, the debugfs counters are only available when debugfs is readable.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ZswapStats {
+    pub enabled: bool,
+    pub compressor: String,
+    pub max_pool_percent: u64,
+    pub pool_size: u64,
+    pub stored_size: u64,
+    pub stored_pages: Option<u64>,
+    pub written_back_pages: Option<u64>,
+    pub rejected_pages: Option<u64>,
+    pub pool_limit_hit: Option<u64>,
+}
+
+impl ZswapStats {
+    /// Original size of the stored pages divided by the pool size.
+    pub fn compression_ratio(&self) -> f64 {
+        if self.pool_size == 0 {
+            0.0
+        } else {
+            self.stored_size as f64 / self.pool_size as f64
+        }
+    }
+}
+
+fn read_value(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_owned())
+}
+
+fn read_counter(debugfs: &Path, name: &str) -> Option<u64> {
+    read_value(&debugfs.join(name))?.parse().ok()
+}
+
+/// Sums every `reject_*` counter, the kernel keeps one per rejection reason.
+fn read_rejected(debugfs: &Path) -> Option<u64> {
+    let entries = fs::read_dir(debugfs).ok()?;
+    let counters: Vec<u64> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("reject_"))
+        .filter_map(|entry| read_value(&entry.path())?.parse().ok())
+        .collect();
+    (!counters.is_empty()).then(|| counters.iter().sum())
+}
+
+/// Returns `None` when the kernel was built without zswap. `pool_size` and
+/// `stored_size` are the `Zswap` and `Zswapped` lines of `/proc/meminfo`.
+pub fn get_zswap_stats(pool_size: Option<u64>, stored_size: Option<u64>) -> Option<ZswapStats> {
+    read_zswap_stats(
+        Path::new(PARAMETERS),
+        Path::new(DEBUGFS),
+        pool_size,
+        stored_size,
+    )
+}
+
+/// Reads the module parameters and debugfs counters from the given directories.
+fn read_zswap_stats(
+    parameters: &Path,
+    debugfs: &Path,
+    pool_size: Option<u64>,
+    stored_size: Option<u64>,
+) -> Option<ZswapStats> {
+    let enabled = read_value(&parameters.join("enabled"))?;
+
+    Some(ZswapStats {
+        enabled: enabled == "Y" || enabled == "1",
+        compressor: read_value(&parameters.join("compressor")).unwrap_or_else(|| "unknown".into()),
+        max_pool_percent: read_value(&parameters.join("max_pool_percent"))
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default(),
+        pool_size: pool_size.unwrap_or_default(),
+        stored_size: stored_size.unwrap_or_default(),
+        stored_pages: read_counter(debugfs, "stored_pages"),
+        written_back_pages: read_counter(debugfs, "written_back_pages"),
+        rejected_pages: read_rejected(debugfs),
+        pool_limit_hit: read_counter(debugfs, "pool_limit_hit"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Fake `parameters` and debugfs directories, removed again when dropped.
+    struct SysDirs(PathBuf);
+
+    impl SysDirs {
+        fn new(test: &str, parameters: &[(&str, &str)], debugfs: &[(&str, &str)]) -> Self {
+            let root = std::env::temp_dir().join(format!("swaptop-{}-{test}", std::process::id()));
+            for (dir, files) in [("parameters", parameters), ("debugfs", debugfs)] {
+                fs::create_dir_all(root.join(dir)).unwrap();
+                for (name, content) in files {
+                    fs::write(root.join(dir).join(name), content).unwrap();
+                }
+            }
+            Self(root)
+        }
+
+        fn read(&self, pool_size: Option<u64>, stored_size: Option<u64>) -> Option<ZswapStats> {
+            read_zswap_stats(
+                &self.0.join("parameters"),
+                &self.0.join("debugfs"),
+                pool_size,
+                stored_size,
+            )
+        }
+    }
+
+    impl Drop for SysDirs {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn reads_parameters_and_counters() {
+        let dirs = SysDirs::new(
+            "zswap-full",
+            &[
+                ("enabled", "Y\n"),
+                ("compressor", "zstd\n"),
+                ("max_pool_percent", "20\n"),
+            ],
+            &[
+                ("stored_pages", "2500\n"),
+                ("written_back_pages", "40\n"),
+                ("pool_limit_hit", "2\n"),
+                ("reject_compress_poor", "3\n"),
+                ("reject_alloc_fail", "4\n"),
+                ("reject_kmemcache_fail", "0\n"),
+            ],
+        );
+        let stats = dirs.read(Some(4 << 20), Some(10 << 20)).unwrap();
+        assert!(stats.enabled);
+        assert_eq!(stats.compressor, "zstd");
+        assert_eq!(stats.max_pool_percent, 20);
+        assert_eq!((stats.pool_size, stats.stored_size), (4 << 20, 10 << 20));
+        assert_eq!(stats.stored_pages, Some(2500));
+        assert_eq!(stats.written_back_pages, Some(40));
+        assert_eq!(stats.rejected_pages, Some(7));
+        assert_eq!(stats.pool_limit_hit, Some(2));
+        assert_eq!(stats.compression_ratio(), 2.5);
+    }
+
+    #[test]
+    fn counters_need_debugfs() {
+        let dirs = SysDirs::new("zswap-no-debugfs", &[("enabled", "N")], &[]);
+        let stats = dirs.read(None, None).unwrap();
+        assert!(!stats.enabled);
+        assert_eq!(stats.compressor, "unknown");
+        assert_eq!(stats.stored_pages, None);
+        assert_eq!(stats.rejected_pages, None);
+        assert_eq!(stats.compression_ratio(), 0.0);
+    }
+
+    #[test]
+    fn missing_module_is_none() {
+        let dirs = SysDirs::new("zswap-missing", &[], &[]);
+        assert!(dirs.read(Some(0), Some(0)).is_none());
+    }
+}